@@ -1,21 +1,49 @@
+use chrono::Local;
+
+use crate::astro;
 use crate::cli::Args;
-use crate::effects::shooting_star::ShootingStarManager;
-use crate::effects::starfield::Starfield;
-use crate::theme::Theme;
+use crate::color::ColorMode;
+use crate::effects::{self, ClearZone, Effect, EffectEvent, EffectKind, EffectSettings};
+use crate::generate;
+use crate::schedule::ThemeSchedule;
+use crate::theme::{Theme, ThemeOverrides, ThemeTransition, BUILTIN_THEMES};
+
+/// Redraw rate with reduced motion: enough to keep the seconds current.
+const REDUCED_MOTION_FPS: u32 = 1;
 
 /// Application state.
 pub struct App {
+    /// Theme as currently displayed (possibly mid-transition)
     pub theme: Theme,
+    /// Theme being shown or faded towards when no schedule is active
+    pub base_theme: Theme,
+    /// Day/night schedule driving `theme`, when `--auto-theme` is set
+    pub schedule: Option<ThemeSchedule>,
+    pub transition: Option<ThemeTransition>,
+    /// Crossfade duration in seconds for runtime theme changes
+    pub transition_secs: f32,
+    /// Command-line color tweaks applied to every theme
+    pub overrides: ThemeOverrides,
     pub time_format: String,
     pub show_date: bool,
     pub blink: bool,
     pub blink_visible: bool,
     pub effects_enabled: bool,
-    pub starfield: Starfield,
-    pub shooting_stars: ShootingStarManager,
+    /// Hold every animation still
+    pub reduced_motion: bool,
+    pub color_mode: ColorMode,
+    /// Leave the terminal's own background showing instead of `theme.bg`
+    pub transparent: bool,
+    /// How stars treat the area around the clock
+    pub clear_zone: ClearZone,
+    /// Margin of that area around the clock and date, in columns
+    pub clear_margin: u16,
+    /// Enabled effects, sorted by z-order
+    pub effects: Vec<Box<dyn Effect>>,
+    pub effect_kinds: Vec<EffectKind>,
+    pub effect_settings: EffectSettings,
     pub fps: u32,
     pub scale_factor: u32,
-    pub star_density: u32,
     pub tick_count: u64,
     pub elapsed: f32,
     pub should_quit: bool,
@@ -26,25 +54,41 @@ pub struct App {
 impl App {
     /// Create from CLI args and initial terminal size.
     pub fn new(args: &Args, width: u16, height: u16) -> Self {
-        let mut theme = Theme::by_name(&args.theme);
-        if let Some(ref c) = args.color {
-            theme = theme.with_accent_override(c);
-        }
+        let (overrides, schedule, base_theme) = themes_from_args(args, Theme::by_name);
+        let theme = match schedule {
+            Some(ref s) => s.theme_at(Local::now()),
+            None => base_theme.clone(),
+        };
 
         let time_format = args.effective_format();
 
         Self {
             theme,
+            base_theme,
+            schedule,
+            transition: None,
+            transition_secs: transition_secs(args),
+            overrides,
             time_format,
             show_date: args.date,
-            blink: args.blink,
+            blink: args.blink && !args.reduced_motion,
             blink_visible: true,
             effects_enabled: !args.no_effects,
-            starfield: Starfield::new(width, height, args.stars, args.seed),
-            shooting_stars: ShootingStarManager::new(width, height, args.seed),
-            fps: args.fps,
+            reduced_motion: args.reduced_motion,
+            color_mode: args.color_mode().unwrap_or_else(ColorMode::detect),
+            transparent: args.transparent,
+            clear_zone: args.clear_zone,
+            clear_margin: args.clear_margin,
+            effects: effects::build(
+                &args.enabled_effects(),
+                width,
+                height,
+                &args.effect_settings(),
+            ),
+            effect_kinds: args.enabled_effects(),
+            effect_settings: args.effect_settings(),
+            fps: frame_rate(args),
             scale_factor: args.size,
-            star_density: args.stars,
             tick_count: 0,
             elapsed: 0.0,
             should_quit: false,
@@ -53,27 +97,268 @@ impl App {
         }
     }
 
-    /// Advance one frame. `dt` = seconds since last frame.
+    /// Apply new settings while running (config or theme file edited).
+    /// The theme crossfades to its new look if it has one; effects are
+    /// rebuilt only when their selection or settings change. Unlike startup, a theme file that
+    /// fails to load is an error here and nothing is changed.
+    pub fn reload(&mut self, args: &Args) -> Result<(), String> {
+        let (overrides, schedule, base_theme) = load_themes(args)?;
+
+        let before = self.target_theme();
+        self.transition_secs = transition_secs(args);
+        self.overrides = overrides;
+        self.schedule = schedule;
+        self.base_theme = base_theme;
+        if self.target_theme() != before {
+            self.transition = Some(ThemeTransition::new(
+                self.theme.clone(),
+                self.transition_secs,
+            ));
+        }
+        self.time_format = args.effective_format();
+        self.show_date = args.date;
+        self.blink = args.blink && !args.reduced_motion;
+        self.blink_visible = true;
+        self.effects_enabled = !args.no_effects;
+        self.reduced_motion = args.reduced_motion;
+        self.color_mode = args.color_mode().unwrap_or_else(ColorMode::detect);
+        self.transparent = args.transparent;
+        self.clear_zone = args.clear_zone;
+        self.clear_margin = args.clear_margin;
+        self.fps = frame_rate(args);
+        self.scale_factor = args.size;
+        let kinds = args.enabled_effects();
+        let settings = args.effect_settings();
+        if kinds != self.effect_kinds || settings != self.effect_settings {
+            self.effects = effects::build(&kinds, self.width, self.height, &settings);
+            self.effect_kinds = kinds;
+            self.effect_settings = settings;
+        }
+        Ok(())
+    }
+
+    /// Advance one frame. `dt` = seconds since last frame. With reduced
+    /// motion the animation clock stands still, so effects, scanlines and
+    /// gradients hold their pose; themes still follow the schedule.
     pub fn tick(&mut self, dt: f32) {
         self.tick_count += 1;
+        self.update_theme(dt);
+        if self.reduced_motion {
+            return;
+        }
         self.elapsed += dt;
 
         // Blink colon every 0.5s
         if self.blink {
-            self.blink_visible = (self.elapsed * 2.0) as u64 % 2 == 0;
+            self.blink_visible = ((self.elapsed * 2.0) as u64).is_multiple_of(2);
         }
 
         if self.effects_enabled {
-            self.starfield.tick(dt);
-            self.shooting_stars.tick(dt);
+            for effect in &mut self.effects {
+                effect.tick(dt);
+            }
+        }
+    }
+
+    /// The theme to display once any transition has finished.
+    fn target_theme(&self) -> Theme {
+        match self.schedule {
+            Some(ref schedule) => schedule.theme_at(Local::now()),
+            None => self.base_theme.clone(),
+        }
+    }
+
+    fn update_theme(&mut self, dt: f32) {
+        let target = self.target_theme();
+        self.theme = match self.transition {
+            Some(ref mut transition) => {
+                transition.advance(dt);
+                transition.apply(&target)
+            }
+            None => target,
+        };
+        if self.transition.as_ref().is_some_and(|t| t.is_finished()) {
+            self.transition = None;
         }
     }
 
+    /// Switch to `theme`, crossfading from whatever is on screen.
+    /// A manual switch takes over from the day/night schedule.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.schedule = None;
+        self.base_theme = self.overrides.apply(theme);
+        self.transition = Some(ThemeTransition::new(
+            self.theme.clone(),
+            self.transition_secs,
+        ));
+    }
+
+    /// Pass a user action on to the effects. Ignored with reduced motion.
+    pub fn send_effect_event(&mut self, event: EffectEvent) {
+        if self.reduced_motion {
+            return;
+        }
+        for effect in &mut self.effects {
+            effect.handle(event);
+        }
+    }
+
+    /// Advance to the next built-in theme.
+    pub fn cycle_theme(&mut self) {
+        let current = BUILTIN_THEMES
+            .iter()
+            .position(|&n| n == self.base_theme.name)
+            .unwrap_or(0);
+        let next = BUILTIN_THEMES[(current + 1) % BUILTIN_THEMES.len()];
+        self.set_theme(Theme::by_name(next));
+    }
+
     /// Handle terminal resize.
     pub fn resize(&mut self, width: u16, height: u16) {
         self.width = width;
         self.height = height;
-        self.starfield.resize(width, height, self.star_density);
-        self.shooting_stars.resize(width, height);
+        for effect in &mut self.effects {
+            effect.resize(width, height);
+        }
+    }
+}
+
+/// Theme crossfade duration; reduced motion switches instantly.
+fn transition_secs(args: &Args) -> f32 {
+    if args.reduced_motion {
+        0.0
+    } else {
+        args.transition
+    }
+}
+
+/// Target frames per second.
+fn frame_rate(args: &Args) -> u32 {
+    if args.reduced_motion {
+        REDUCED_MOTION_FPS
+    } else {
+        args.fps
+    }
+}
+
+/// Check that every theme `args` uses loads, so a broken theme file is
+/// reported at startup instead of falling back to the default.
+pub fn check_themes(args: &Args) -> Result<(), String> {
+    load_themes(args).map(|_| ())
+}
+
+/// Like [`themes_from_args`], but failing on the first theme that does not
+/// load.
+fn load_themes(args: &Args) -> Result<(ThemeOverrides, Option<ThemeSchedule>, Theme), String> {
+    let mut error = None;
+    let themes = themes_from_args(args, |name| {
+        Theme::load(name).unwrap_or_else(|e| {
+            error.get_or_insert(e);
+            Theme::void()
+        })
+    });
+    match error {
+        Some(e) => Err(e),
+        None => Ok(themes),
+    }
+}
+
+/// Build the command-line theme overrides, the optional day/night schedule
+/// and the fixed theme from `args`, resolving theme names with `load`.
+fn themes_from_args(
+    args: &Args,
+    mut load: impl FnMut(&str) -> Theme,
+) -> (ThemeOverrides, Option<ThemeSchedule>, Theme) {
+    let overrides = ThemeOverrides {
+        accent: args.color,
+        colors: args.theme_set.clone(),
+        gradient: args.gradient,
+    };
+
+    let schedule = if args.auto_theme {
+        let location = args.location();
+        // Scanning the day for sun events is not free; only do it for the log
+        if let Some(loc) = location.filter(|_| tracing::enabled!(tracing::Level::INFO)) {
+            let today = Local::now().date_naive();
+            let times = astro::sun_times(today, &Local, loc);
+            tracing::info!(
+                "sun today: dawn {:?}, sunrise {:?}, sunset {:?}, dusk {:?}",
+                times.dawn,
+                times.sunrise,
+                times.sunset,
+                times.dusk
+            );
+        }
+        Some(ThemeSchedule::new(
+            overrides.apply(load(&args.day_theme)),
+            overrides.apply(load(&args.night_theme)),
+            location,
+        ))
+    } else {
+        None
+    };
+    let base_theme = overrides.apply(match args.theme_from {
+        Some(seed) => generate::theme_from_seed("custom", seed),
+        None => load(&args.theme),
+    });
+    (overrides, schedule, base_theme)
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    fn args(extra: &[&str]) -> Args {
+        let base = ["stardial", "--seed", "42", "--color-mode", "truecolor"];
+        Args::parse_from(base.iter().chain(extra))
+    }
+
+    #[test]
+    fn test_reload_applies_new_settings() {
+        let mut app = App::new(&args(&[]), 80, 24);
+        let args = args(&[
+            "--theme=solar",
+            "--fps=12",
+            "--stars=90",
+            "--effects=starfield",
+            "--date",
+        ]);
+        app.reload(&args).unwrap();
+        assert_eq!(app.base_theme.name, "solar");
+        assert_eq!(app.fps, 12);
+        assert!(app.show_date);
+        assert!(app.transition.is_some(), "theme change should crossfade");
+        assert_eq!(app.effect_settings.stars, 90);
+        assert_eq!(app.effects.len(), 1, "effects should be rebuilt");
+    }
+
+    #[test]
+    fn test_reload_without_theme_change_does_not_fade() {
+        let mut app = App::new(&args(&["--theme=luna"]), 80, 24);
+        app.reload(&args(&["--theme=luna", "--fps=12"])).unwrap();
+        assert_eq!(app.fps, 12);
+        assert!(app.transition.is_none());
+    }
+
+    #[test]
+    fn test_reload_keeps_settings_on_bad_theme() {
+        let mut app = App::new(&args(&[]), 80, 24);
+        let args = args(&["--theme", "no-such-theme-anywhere", "--fps", "5"]);
+        assert!(app.reload(&args).is_err());
+        assert_eq!(app.base_theme.name, "void");
+        assert_eq!(app.fps, 30);
+    }
+
+    #[test]
+    fn test_check_themes() {
+        assert!(check_themes(&args(&["--theme", "luna"])).is_ok());
+        let err = check_themes(&args(&["--theme", "no-such-theme-anywhere"])).unwrap_err();
+        assert!(err.contains("unknown theme"));
+        // Day and night themes only matter with --auto-theme
+        assert!(check_themes(&args(&["--day-theme", "no-such-theme-anywhere"])).is_ok());
+        let auto = args(&["--auto-theme", "--day-theme", "no-such-theme-anywhere"]);
+        assert!(check_themes(&auto).is_err());
     }
 }
@@ -0,0 +1,193 @@
+//! Lightweight astronomy helpers: sun position, sunrise/sunset times and the
+//! phase of the moon.
+//!
+//! Uses the low-precision NOAA/Almanac formulas, which are accurate to about
+//! a minute — plenty for picking a theme, and needing no network or tables.
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+
+/// Solar elevation (degrees) at which sunrise/sunset happens, accounting
+/// for refraction and the sun's apparent radius.
+pub const SUNRISE_ELEVATION: f64 = -0.833;
+
+/// Solar elevation (degrees) marking the start/end of civil twilight.
+pub const CIVIL_TWILIGHT_ELEVATION: f64 = -6.0;
+
+/// Mean length of the lunar cycle (new moon to new moon) in days.
+const SYNODIC_MONTH: f64 = 29.530_588_853;
+
+/// A new moon (2000-01-06 18:14 UTC), in days since J2000.0.
+const REFERENCE_NEW_MOON: f64 = 5.2597;
+
+/// An observer location in decimal degrees (north and east positive).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+/// Twilight and sunrise/sunset times for a single local day.
+/// Any entry is `None` when the event does not occur (polar day/night).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunTimes {
+    pub dawn: Option<NaiveTime>,
+    pub sunrise: Option<NaiveTime>,
+    pub sunset: Option<NaiveTime>,
+    pub dusk: Option<NaiveTime>,
+}
+
+/// Days since the J2000.0 epoch (2000-01-01 12:00 UTC).
+fn days_since_j2000(t: DateTime<Utc>) -> f64 {
+    let unix = t.timestamp() as f64 + t.timestamp_subsec_millis() as f64 / 1000.0;
+    unix / 86400.0 + 2440587.5 - 2451545.0
+}
+
+/// Local mean sidereal time in degrees (0-360) at longitude `lon`.
+pub fn local_sidereal_degrees(t: DateTime<Utc>, lon: f64) -> f64 {
+    let n = days_since_j2000(t);
+    let gmst = 280.460_618_37 + 360.985_647_366_29 * n;
+    (gmst + lon).rem_euclid(360.0)
+}
+
+/// Sun's apparent right ascension and declination, both in degrees.
+fn sun_equatorial(t: DateTime<Utc>) -> (f64, f64) {
+    let n = days_since_j2000(t);
+    let mean_lon = (280.460 + 0.985_647_4 * n).rem_euclid(360.0);
+    let anomaly = (357.528 + 0.985_600_3 * n).rem_euclid(360.0).to_radians();
+    let ecliptic_lon =
+        (mean_lon + 1.915 * anomaly.sin() + 0.020 * (2.0 * anomaly).sin()).to_radians();
+    let obliquity = (23.439 - 0.000_000_4 * n).to_radians();
+
+    let ra = (obliquity.cos() * ecliptic_lon.sin())
+        .atan2(ecliptic_lon.cos())
+        .to_degrees();
+    let dec = (obliquity.sin() * ecliptic_lon.sin()).asin().to_degrees();
+    (ra.rem_euclid(360.0), dec)
+}
+
+/// Solar elevation above the horizon in degrees.
+pub fn sun_elevation(t: DateTime<Utc>, loc: Location) -> f64 {
+    let (ra, dec) = sun_equatorial(t);
+    let hour_angle = (local_sidereal_degrees(t, loc.lon) - ra).to_radians();
+    let lat = loc.lat.to_radians();
+    let dec = dec.to_radians();
+    (lat.sin() * dec.sin() + lat.cos() * dec.cos() * hour_angle.cos())
+        .asin()
+        .to_degrees()
+}
+
+/// Phase of the moon as a fraction of its cycle: 0 = new, 0.25 = first
+/// quarter, 0.5 = full, 0.75 = last quarter.
+///
+/// Counts mean cycles from a known new moon, which can be off by up to
+/// about half a day — invisible on a sprite a few cells wide.
+pub fn moon_phase(t: DateTime<Utc>) -> f64 {
+    ((days_since_j2000(t) - REFERENCE_NEW_MOON) / SYNODIC_MONTH).rem_euclid(1.0)
+}
+
+/// Compute dawn, sunrise, sunset and dusk for `date` in time zone `tz`.
+///
+/// Scans the day minute by minute for elevation crossings, which is cheap
+/// and naturally handles days where an event never happens.
+pub fn sun_times<Tz: TimeZone>(date: NaiveDate, tz: &Tz, loc: Location) -> SunTimes {
+    let mut times = SunTimes {
+        dawn: None,
+        sunrise: None,
+        sunset: None,
+        dusk: None,
+    };
+    let Some(start) = tz
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()
+    else {
+        return times;
+    };
+
+    let mut prev = sun_elevation(start.with_timezone(&Utc), loc);
+    for minute in 1..=1440 {
+        let t = start.clone() + Duration::minutes(minute);
+        let elev = sun_elevation(t.with_timezone(&Utc), loc);
+        let time = Some(t.time());
+        if prev < CIVIL_TWILIGHT_ELEVATION && elev >= CIVIL_TWILIGHT_ELEVATION {
+            times.dawn = times.dawn.or(time);
+        }
+        if prev < SUNRISE_ELEVATION && elev >= SUNRISE_ELEVATION {
+            times.sunrise = times.sunrise.or(time);
+        }
+        if prev >= SUNRISE_ELEVATION && elev < SUNRISE_ELEVATION {
+            times.sunset = times.sunset.or(time);
+        }
+        if prev >= CIVIL_TWILIGHT_ELEVATION && elev < CIVIL_TWILIGHT_ELEVATION {
+            times.dusk = times.dusk.or(time);
+        }
+        prev = elev;
+    }
+    times
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GREENWICH: Location = Location {
+        lat: 51.4779,
+        lon: 0.0,
+    };
+
+    #[test]
+    fn test_equinox_noon_elevation() {
+        // Near the March equinox the noon sun stands at roughly 90° - latitude.
+        let t = Utc.with_ymd_and_hms(2024, 3, 20, 12, 7, 0).unwrap();
+        let elev = sun_elevation(t, GREENWICH);
+        assert!((elev - (90.0 - GREENWICH.lat)).abs() < 1.0, "elev = {elev}");
+    }
+
+    #[test]
+    fn test_midnight_is_below_horizon() {
+        let t = Utc.with_ymd_and_hms(2024, 6, 21, 0, 0, 0).unwrap();
+        assert!(sun_elevation(t, GREENWICH) < 0.0);
+    }
+
+    #[test]
+    fn test_polar_night_has_no_sunrise() {
+        let svalbard = Location {
+            lat: 78.2,
+            lon: 15.6,
+        };
+        let date = NaiveDate::from_ymd_opt(2024, 12, 21).unwrap();
+        let times = sun_times(date, &Utc, svalbard);
+        assert_eq!(times.sunrise, None);
+        assert_eq!(times.sunset, None);
+    }
+
+    #[test]
+    fn test_event_order() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 20).unwrap();
+        let t = sun_times(date, &Utc, GREENWICH);
+        let (dawn, rise, set, dusk) = (
+            t.dawn.unwrap(),
+            t.sunrise.unwrap(),
+            t.sunset.unwrap(),
+            t.dusk.unwrap(),
+        );
+        assert!(dawn < rise && rise < set && set < dusk);
+    }
+
+    #[test]
+    fn test_moon_phase() {
+        let full = Utc.with_ymd_and_hms(2024, 1, 25, 17, 54, 0).unwrap();
+        assert!((moon_phase(full) - 0.5).abs() < 0.03);
+        let new = Utc.with_ymd_and_hms(2024, 4, 8, 18, 21, 0).unwrap();
+        let phase = moon_phase(new);
+        assert!(!(0.03..=0.97).contains(&phase), "phase = {phase}");
+        let first_quarter = Utc.with_ymd_and_hms(2024, 9, 11, 6, 6, 0).unwrap();
+        assert!((moon_phase(first_quarter) - 0.25).abs() < 0.03);
+    }
+
+    #[test]
+    fn test_sidereal_time_range() {
+        let t = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let lst = local_sidereal_degrees(t, -120.0);
+        assert!((0.0..360.0).contains(&lst));
+    }
+}
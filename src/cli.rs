@@ -1,4 +1,18 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use ratatui::style::Color;
+
+use crate::astro::Location;
+use crate::color::ColorMode;
+use crate::effects::crt::{self, CrtPass};
+use crate::effects::meteor_shower::MeteorShower;
+use crate::effects::starfield::{self, StarDirection, StarMode};
+use crate::effects::{
+    glow, scanline, shooting_star, ClearZone, EffectKind, EffectSettings, DEFAULT_CLEAR_MARGIN,
+    DEFAULT_EFFECTS,
+};
+use crate::theme::{parse_color, parse_color_setting, GradientMode};
 
 /// stardial — a space-anime themed terminal clock
 ///
@@ -11,10 +25,17 @@ use clap::Parser;
 ///   stardial --theme nebula      # nebula color theme
 ///   stardial --no-effects        # plain big clock, no stars
 ///   stardial --color '#ff6ac1'   # custom accent color
+///   stardial --theme-set bg=#101020 --theme-set star_dim=gray
 ///   stardial --stars 80 --fps 24 # dense stars, 24fps
+///   stardial --auto-theme --lat 35.7 --lon 139.7  # day/night by the sun
+///   stardial --theme-from '#ff6ac1'                 # theme from one color
+///   stardial theme generate '#ff6ac1' > sakura.toml
 #[derive(Parser, Debug, Clone)]
 #[command(name = "stardial", version, about, long_about = None)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Time format string (chrono syntax)
     #[arg(long, default_value = "%H:%M:%S")]
     pub format: String,
@@ -43,18 +64,165 @@ pub struct Args {
     #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=60))]
     pub fps: u32,
 
-    /// Color theme
-    #[arg(long, default_value = "void", value_parser = ["void", "nebula", "luna", "solar"])]
+    /// Hold everything still: no drifting stars, shooting stars, weather,
+    /// blinking or fades, and one redraw a second
+    #[arg(long)]
+    pub reduced_motion: bool,
+
+    /// Color theme: void, nebula, luna, solar, or a user theme name/file
+    #[arg(long, default_value = "void")]
     pub theme: String,
 
-    /// Override primary accent color (name or #hex)
+    /// Derive a complete theme from a single accent color (overrides --theme)
+    #[arg(long, value_name = "COLOR", value_parser = parse_color, conflicts_with = "theme")]
+    pub theme_from: Option<Color>,
+
+    /// Switch between day and night themes automatically (overrides --theme)
+    #[arg(long)]
+    pub auto_theme: bool,
+
+    /// Theme shown during the day with --auto-theme
+    #[arg(long, default_value = "solar")]
+    pub day_theme: String,
+
+    /// Theme shown at night with --auto-theme
+    #[arg(long, default_value = "void")]
+    pub night_theme: String,
+
+    /// Crossfade duration in seconds when the theme changes (0 = instant)
+    #[arg(long, default_value_t = 1.5, value_name = "SECS", value_parser = parse_transition)]
+    pub transition: f32,
+
+    /// Latitude in decimal degrees, north positive (enables sunrise/sunset tracking)
+    #[arg(long, requires = "lon", allow_negative_numbers = true, value_parser = parse_latitude)]
+    pub lat: Option<f64>,
+
+    /// Longitude in decimal degrees, east positive
+    #[arg(long, requires = "lat", allow_negative_numbers = true, value_parser = parse_longitude)]
+    pub lon: Option<f64>,
+
+    /// Override primary accent color (#hex, rgb(), hsl(), ansi:N, or a CSS/X11 name)
+    #[arg(long, value_parser = parse_color)]
+    pub color: Option<Color>,
+
+    /// Override any theme color (repeatable): accent, secondary, date_color,
+    /// star_bright, star_dim, shooting_star, scanline, aurora, bg
+    #[arg(long = "theme-set", value_name = "KEY=COLOR", value_parser = parse_color_setting)]
+    pub theme_set: Vec<(String, Color)>,
+
+    /// Color the digits with a gradient (uses the theme's stops, or accent → secondary)
+    #[arg(long, value_enum)]
+    pub gradient: Option<GradientMode>,
+
+    /// Color palette to render with (default: detected from the terminal)
+    #[arg(long, value_enum)]
+    pub color_mode: Option<ColorMode>,
+
+    /// No color: render with characters and bold/dim only (same as NO_COLOR)
+    #[arg(long, conflicts_with = "color_mode")]
+    pub mono: bool,
+
+    /// Keep the terminal's own background (image, transparency) instead of the theme's
     #[arg(long)]
-    pub color: Option<String>,
+    pub transparent: bool,
 
     /// Disable all background effects
     #[arg(long)]
     pub no_effects: bool,
 
+    /// Effects to draw, comma-separated: starfield, shooting-stars, scanlines,
+    /// aurora, nebula, planets, constellations, glow, crt, snow, rain, dust
+    #[arg(long, value_enum, value_delimiter = ',', default_value = DEFAULT_EFFECTS)]
+    pub effects: Vec<EffectKind>,
+
+    /// Turn off the starfield
+    #[arg(long)]
+    pub no_starfield: bool,
+
+    /// Turn off shooting stars
+    #[arg(long)]
+    pub no_shooting_stars: bool,
+
+    /// Turn off the scanline overlay
+    #[arg(long)]
+    pub no_scanlines: bool,
+
+    /// Stars near the clock: dim them, hide them, or leave them (off)
+    #[arg(long, value_enum, default_value = "dim", value_name = "MODE")]
+    pub clear_zone: ClearZone,
+
+    /// Margin of the clear zone around the clock and date, in columns
+    /// (half as many rows)
+    #[arg(long, default_value_t = DEFAULT_CLEAR_MARGIN, value_name = "CELLS", value_parser = clap::value_parser!(u16).range(0..=20))]
+    pub clear_margin: u16,
+
+    /// Star drift speeds in cells/sec for the far, mid and near layers
+    #[arg(long, default_value = "0.2,0.5,1.0", value_name = "FAR,MID,NEAR", value_parser = parse_parallax)]
+    pub parallax: [f32; 3],
+
+    /// How stars move: drift sideways, or stream out of the center (warp)
+    #[arg(long, value_enum, default_value = "drift")]
+    pub star_mode: StarMode,
+
+    /// Star drift direction: degrees (0 = right, 90 = up), left, right, up, down, or still
+    #[arg(long, default_value = "left", value_name = "ANGLE", allow_negative_numbers = true, value_parser = StarDirection::parse)]
+    pub star_direction: StarDirection,
+
+    /// Multiplier on star drift speeds
+    #[arg(long, default_value_t = 1.0, value_name = "FACTOR", value_parser = parse_speed)]
+    pub star_speed: f32,
+
+    /// Star twinkle amplitude (0 = steady, 1 = full on/off)
+    #[arg(long, default_value_t = starfield::DEFAULT_TWINKLE, value_name = "AMOUNT", value_parser = parse_fraction)]
+    pub twinkle: f32,
+
+    /// Seconds between shooting stars, as MIN-MAX or a fixed value
+    #[arg(long, default_value = "5-15", value_name = "SECS", value_parser = parse_interval_range)]
+    pub shooting_star_interval: (f32, f32),
+
+    /// Shooting star speed in cells/sec, as MIN-MAX or a fixed value
+    #[arg(long, default_value = "20-50", value_name = "SPEED", value_parser = parse_speed_range)]
+    pub shooting_star_speed: (f32, f32),
+
+    /// Shooting star directions in degrees as MIN-MAX (0 = right, 90 = up);
+    /// ranges may pass 360, e.g. 330-390
+    #[arg(long, default_value = "0-360", value_name = "DEGREES", value_parser = parse_range)]
+    pub shooting_star_angle: (f32, f32),
+
+    /// How much shooting star paths bend (0 = straight, 1 = strongly curved)
+    #[arg(long, default_value_t = shooting_star::DEFAULT_CURVE, value_name = "AMOUNT", value_parser = parse_fraction)]
+    pub shooting_star_curve: f32,
+
+    /// Run a meteor shower at its peak, e.g. for a demo. Showers also
+    /// happen by themselves around their real dates
+    #[arg(long, value_enum, value_name = "SHOWER")]
+    pub meteor_shower: Option<MeteorShower>,
+
+    /// Peak darkening of scanline rows (0-1)
+    #[arg(long, default_value_t = scanline::DEFAULT_OPACITY, value_name = "AMOUNT", value_parser = parse_fraction)]
+    pub scanline_opacity: f32,
+
+    /// How fast the scanlines breathe (0 = still)
+    #[arg(long, default_value_t = scanline::DEFAULT_SPEED, value_name = "SPEED", value_parser = parse_speed)]
+    pub scanline_speed: f32,
+
+    /// How far the glow reaches around the digits, in columns (1-8)
+    #[arg(long, default_value_t = glow::DEFAULT_RADIUS, value_name = "CELLS", value_parser = clap::value_parser!(u16).range(1..=8))]
+    pub glow_radius: u16,
+
+    /// Strength of the glow next to the digits (0-1)
+    #[arg(long, default_value_t = glow::DEFAULT_INTENSITY, value_name = "AMOUNT", value_parser = parse_fraction)]
+    pub glow_intensity: f32,
+
+    /// Pulse the glow in time with the blinking colons
+    #[arg(long)]
+    pub glow_pulse: bool,
+
+    /// CRT passes to run with the crt effect, comma-separated: vignette,
+    /// flicker, persistence, chromatic
+    #[arg(long, value_enum, value_delimiter = ',', default_value = crt::DEFAULT_PASSES)]
+    pub crt: Vec<CrtPass>,
+
     /// Star density (0-100)
     #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u32).range(0..=100))]
     pub stars: u32,
@@ -70,6 +238,40 @@ pub struct Args {
     /// Write debug logs to file
     #[arg(long, value_name = "FILE")]
     pub log: Option<String>,
+
+    /// Config file with default options (default: ~/.config/stardial/config.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// List built-in and user themes
+    Themes {
+        /// Render a small sample of each theme side by side
+        #[arg(long)]
+        preview: bool,
+    },
+
+    /// Theme tools
+    Theme {
+        #[command(subcommand)]
+        action: ThemeCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum ThemeCommand {
+    /// Print a complete theme file derived from one seed color
+    Generate {
+        /// Seed color, used as the accent
+        #[arg(value_parser = parse_color)]
+        color: Color,
+
+        /// Theme name written into the file
+        #[arg(long, default_value = "generated")]
+        name: String,
+    },
 }
 
 impl Args {
@@ -87,6 +289,186 @@ impl Args {
             self.format.clone()
         }
     }
+
+    /// Color mode requested on the command line, if any. `None` means
+    /// detect from the environment.
+    pub fn color_mode(&self) -> Option<ColorMode> {
+        if self.mono {
+            Some(ColorMode::Mono)
+        } else {
+            self.color_mode
+        }
+    }
+
+    /// Effects to draw: `--effects` minus any individually turned off, and
+    /// shooting stars and weather with reduced motion, since they are
+    /// nothing but motion.
+    pub fn enabled_effects(&self) -> Vec<EffectKind> {
+        self.effects
+            .iter()
+            .copied()
+            .filter(|kind| match kind {
+                EffectKind::Starfield => !self.no_starfield,
+                EffectKind::ShootingStars => !self.no_shooting_stars && !self.reduced_motion,
+                EffectKind::Scanlines => !self.no_scanlines,
+                EffectKind::Snow | EffectKind::Rain | EffectKind::Dust => !self.reduced_motion,
+                EffectKind::Aurora
+                | EffectKind::Nebula
+                | EffectKind::Planets
+                | EffectKind::Constellations
+                | EffectKind::Glow
+                | EffectKind::Crt => true,
+            })
+            .collect()
+    }
+
+    /// Settings the effect constructors need.
+    pub fn effect_settings(&self) -> EffectSettings {
+        EffectSettings {
+            stars: self.stars,
+            seed: self.seed,
+            parallax: self.parallax,
+            twinkle: self.twinkle,
+            // Frozen warp streaks would look broken; still stars are calmer
+            star_mode: if self.reduced_motion {
+                StarMode::Drift
+            } else {
+                self.star_mode
+            },
+            star_direction: self.star_direction,
+            star_speed: self.star_speed,
+            shooting_star_interval: self.shooting_star_interval,
+            shooting_star_speed: self.shooting_star_speed,
+            shooting_star_angle: self.shooting_star_angle,
+            shooting_star_curve: self.shooting_star_curve,
+            meteor_shower: self.meteor_shower,
+            scanline_opacity: self.scanline_opacity,
+            scanline_speed: self.scanline_speed,
+            glow_radius: self.glow_radius,
+            glow_intensity: self.glow_intensity,
+            glow_pulse: self.glow_pulse,
+            // Flicker and fading ghosts would keep a still screen changing
+            crt: if self.reduced_motion {
+                self.crt
+                    .iter()
+                    .copied()
+                    .filter(|pass| !matches!(pass, CrtPass::Flicker | CrtPass::Persistence))
+                    .collect()
+            } else {
+                self.crt.clone()
+            },
+            location: self.location(),
+        }
+    }
+
+    /// Observer location, if both coordinates were given.
+    pub fn location(&self) -> Option<Location> {
+        match (self.lat, self.lon) {
+            (Some(lat), Some(lon)) => Some(Location { lat, lon }),
+            _ => None,
+        }
+    }
+}
+
+fn parse_transition(s: &str) -> Result<f32, String> {
+    let v: f32 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{s}` is not a number"))?;
+    if !(0.0..=60.0).contains(&v) {
+        return Err("must be between 0 and 60 seconds".into());
+    }
+    Ok(v)
+}
+
+fn parse_number(s: &str) -> Result<f32, String> {
+    s.trim()
+        .parse()
+        .map_err(|_| format!("`{s}` is not a number"))
+}
+
+/// A speed or duration: any number from 0 up.
+fn parse_speed(s: &str) -> Result<f32, String> {
+    let v = parse_number(s)?;
+    if v < 0.0 {
+        return Err("must not be negative".into());
+    }
+    Ok(v)
+}
+
+fn parse_fraction(s: &str) -> Result<f32, String> {
+    let v = parse_number(s)?;
+    if !(0.0..=1.0).contains(&v) {
+        return Err("must be between 0 and 1".into());
+    }
+    Ok(v)
+}
+
+/// `MIN-MAX` (or a single value for both), e.g. `5-15`.
+fn parse_range(s: &str) -> Result<(f32, f32), String> {
+    let (min, max) = match s.split_once('-') {
+        Some((min, max)) => (parse_speed(min)?, parse_speed(max)?),
+        None => {
+            let v = parse_speed(s)?;
+            (v, v)
+        }
+    };
+    if min > max {
+        return Err(format!("minimum {min} is larger than maximum {max}"));
+    }
+    Ok((min, max))
+}
+
+/// A range of seconds between shooting stars, at least
+/// [`shooting_star::MIN_INTERVAL`].
+fn parse_interval_range(s: &str) -> Result<(f32, f32), String> {
+    let (min, max) = parse_range(s)?;
+    if min < shooting_star::MIN_INTERVAL {
+        return Err(format!(
+            "must be at least {} seconds",
+            shooting_star::MIN_INTERVAL
+        ));
+    }
+    Ok((min, max))
+}
+
+/// A range of speeds above 0; a star that does not move never leaves.
+fn parse_speed_range(s: &str) -> Result<(f32, f32), String> {
+    let (min, max) = parse_range(s)?;
+    if min <= 0.0 {
+        return Err("must be above 0".into());
+    }
+    Ok((min, max))
+}
+
+/// Three comma-separated layer speeds, far to near.
+fn parse_parallax(s: &str) -> Result<[f32; 3], String> {
+    let speeds = s
+        .split(',')
+        .map(parse_speed)
+        .collect::<Result<Vec<f32>, String>>()?;
+    speeds
+        .try_into()
+        .map_err(|_| "expected three speeds: FAR,MID,NEAR".to_string())
+}
+
+fn parse_latitude(s: &str) -> Result<f64, String> {
+    parse_degrees(s, 90.0)
+}
+
+fn parse_longitude(s: &str) -> Result<f64, String> {
+    parse_degrees(s, 180.0)
+}
+
+fn parse_degrees(s: &str, limit: f64) -> Result<f64, String> {
+    let v: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("`{s}` is not a number"))?;
+    if !(-limit..=limit).contains(&v) {
+        return Err(format!("must be between -{limit} and {limit}"));
+    }
+    Ok(v)
 }
 
 #[cfg(test)]
@@ -132,22 +514,117 @@ mod tests {
         assert_eq!(args.effective_format(), "%H:%M:%S");
     }
 
+    #[test]
+    fn test_mono_flag_sets_color_mode() {
+        let args = Args {
+            mono: true,
+            ..default_args()
+        };
+        assert_eq!(args.color_mode(), Some(ColorMode::Mono));
+        assert_eq!(default_args().color_mode(), None);
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        assert_eq!(parse_latitude("-33.9"), Ok(-33.9));
+        assert_eq!(parse_longitude("151.2"), Ok(151.2));
+        assert!(parse_latitude("91").is_err());
+        assert!(parse_longitude("-180.5").is_err());
+        assert!(parse_latitude("north").is_err());
+    }
+
+    #[test]
+    fn test_parse_transition() {
+        assert_eq!(parse_transition("0"), Ok(0.0));
+        assert_eq!(parse_transition("2.5"), Ok(2.5));
+        assert!(parse_transition("-1").is_err());
+        assert!(parse_transition("soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_effect_tunables() {
+        assert_eq!(parse_range("5-15"), Ok((5.0, 15.0)));
+        assert_eq!(parse_range("2.5"), Ok((2.5, 2.5)));
+        assert!(parse_range("15-5").is_err());
+        assert!(parse_range("fast").is_err());
+        assert_eq!(parse_speed_range("20-50"), Ok((20.0, 50.0)));
+        assert!(parse_speed_range("0-50").is_err());
+        assert!(parse_speed_range("0").is_err());
+        assert_eq!(parse_interval_range("0.5-2"), Ok((0.5, 2.0)));
+        assert!(parse_interval_range("0").is_err());
+        assert!(parse_interval_range("0.1-4").is_err());
+        assert_eq!(parse_parallax("0.1,0.2,0.4"), Ok([0.1, 0.2, 0.4]));
+        assert!(parse_parallax("0.1,0.2").is_err());
+        assert!(parse_fraction("1.5").is_err());
+        assert!(parse_speed("-1").is_err());
+    }
+
+    #[test]
+    fn test_enabled_effects() {
+        let args = Args {
+            no_shooting_stars: true,
+            ..default_args()
+        };
+        assert_eq!(
+            args.enabled_effects(),
+            vec![EffectKind::Starfield, EffectKind::Scanlines]
+        );
+    }
+
+    #[test]
+    fn test_reduced_motion_effects() {
+        let args = Args {
+            reduced_motion: true,
+            star_mode: StarMode::Warp,
+            ..default_args()
+        };
+        assert_eq!(
+            args.enabled_effects(),
+            vec![EffectKind::Starfield, EffectKind::Scanlines]
+        );
+        assert_eq!(args.effect_settings().star_mode, StarMode::Drift);
+        let args = Args {
+            crt: vec![CrtPass::Flicker, CrtPass::Persistence, CrtPass::Chromatic],
+            ..args
+        };
+        assert_eq!(args.effect_settings().crt, vec![CrtPass::Chromatic]);
+
+        let args = Args {
+            effects: vec![
+                EffectKind::Starfield,
+                EffectKind::Snow,
+                EffectKind::Rain,
+                EffectKind::Dust,
+                EffectKind::Aurora,
+            ],
+            ..args
+        };
+        assert_eq!(
+            args.enabled_effects(),
+            vec![EffectKind::Starfield, EffectKind::Aurora]
+        );
+    }
+
+    #[test]
+    fn test_location_requires_both() {
+        let args = Args {
+            lat: Some(10.0),
+            ..default_args()
+        };
+        assert_eq!(args.location(), None);
+        let args = Args {
+            lat: Some(10.0),
+            lon: Some(20.0),
+            ..default_args()
+        };
+        let expected = Location {
+            lat: 10.0,
+            lon: 20.0,
+        };
+        assert_eq!(args.location(), Some(expected));
+    }
+
     fn default_args() -> Args {
-        Args {
-            format: "%H:%M:%S".into(),
-            twelve: false,
-            twentyfour: false,
-            date: false,
-            no_seconds: false,
-            blink: false,
-            fps: 30,
-            theme: "void".into(),
-            color: None,
-            no_effects: false,
-            size: 1,
-            stars: 40,
-            seed: None,
-            log: None,
-        }
+        Args::parse_from(["stardial"])
     }
 }
@@ -0,0 +1,382 @@
+//! Terminal color capability detection and palette quantization.
+//!
+//! Themes are authored in 24-bit RGB. On terminals that cannot show that,
+//! every rendered color is mapped to the nearest entry of the palette the
+//! terminal does support.
+
+use std::path::PathBuf;
+
+use ratatui::style::{Color, Modifier};
+
+use crate::theme::{color_rgb, ANSI_PALETTE};
+
+/// How many colors the terminal can display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorMode {
+    /// 24-bit RGB
+    Truecolor,
+    /// xterm 256-color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 basic ANSI colors
+    #[value(name = "16")]
+    Ansi16,
+    /// No color at all
+    Mono,
+}
+
+impl ColorMode {
+    /// Detect the color mode from `NO_COLOR`, `COLORTERM`, `TERM`,
+    /// `TERM_PROGRAM` and the terminfo database.
+    pub fn detect() -> Self {
+        let var = |k: &str| std::env::var(k).ok().filter(|v| !v.is_empty());
+        // https://no-color.org: any non-empty value disables color
+        if var("NO_COLOR").is_some() {
+            return ColorMode::Mono;
+        }
+        let term = var("TERM");
+        let colors = term.as_deref().and_then(terminfo_colors);
+        classify(
+            var("COLORTERM").as_deref(),
+            term.as_deref(),
+            var("TERM_PROGRAM").as_deref(),
+            colors,
+        )
+    }
+}
+
+/// Decide the color mode from environment values and the terminfo
+/// `colors` capability, most specific signal first.
+fn classify(
+    colorterm: Option<&str>,
+    term: Option<&str>,
+    term_program: Option<&str>,
+    terminfo_colors: Option<u32>,
+) -> ColorMode {
+    if matches!(colorterm, Some("truecolor" | "24bit")) {
+        return ColorMode::Truecolor;
+    }
+    if matches!(
+        term_program,
+        Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+    ) {
+        return ColorMode::Truecolor;
+    }
+    let Some(term) = term else {
+        // Windows terminals don't set TERM but handle truecolor fine.
+        return if cfg!(windows) {
+            ColorMode::Truecolor
+        } else {
+            ColorMode::Ansi16
+        };
+    };
+    if term == "dumb" {
+        return ColorMode::Mono;
+    }
+    if term.ends_with("-direct") || term.contains("truecolor") {
+        return ColorMode::Truecolor;
+    }
+    match terminfo_colors {
+        Some(n) if n >= 1 << 24 => ColorMode::Truecolor,
+        Some(n) if n >= 256 => ColorMode::Ansi256,
+        Some(n) if n >= 8 => ColorMode::Ansi16,
+        Some(_) => ColorMode::Mono,
+        None if term.contains("256color") => ColorMode::Ansi256,
+        None => ColorMode::Ansi16,
+    }
+}
+
+/// Read the numeric `colors` capability for `term` from the compiled
+/// terminfo database.
+fn terminfo_colors(term: &str) -> Option<u32> {
+    let first = term.chars().next()?;
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(dir.into());
+    }
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join(".terminfo"));
+    }
+    if let Ok(list) = std::env::var("TERMINFO_DIRS") {
+        dirs.extend(list.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+    }
+    dirs.extend(
+        ["/etc/terminfo", "/lib/terminfo", "/usr/share/terminfo"]
+            .iter()
+            .map(PathBuf::from),
+    );
+
+    dirs.iter()
+        .flat_map(|d| {
+            [
+                d.join(first.to_string()).join(term),
+                d.join(format!("{:x}", first as u32)).join(term),
+            ]
+        })
+        .find_map(|p| std::fs::read(p).ok())
+        .and_then(|data| parse_terminfo_colors(&data))
+}
+
+/// Index of `colors` among terminfo numeric capabilities.
+const TERMINFO_COLORS_INDEX: usize = 13;
+
+/// Extract the `colors` capability from a compiled terminfo entry
+/// (legacy 16-bit or extended 32-bit number format).
+fn parse_terminfo_colors(data: &[u8]) -> Option<u32> {
+    let word = |i: usize| -> Option<usize> {
+        Some(u16::from_le_bytes([*data.get(i)?, *data.get(i + 1)?]) as usize)
+    };
+    let num_size = match word(0)? {
+        0o432 => 2,
+        0o1036 => 4,
+        _ => return None,
+    };
+    let (names, bools, nums) = (word(2)?, word(4)?, word(6)?);
+    if nums <= TERMINFO_COLORS_INDEX {
+        return None;
+    }
+    let mut offset = 12 + names + bools;
+    // Numbers are aligned to an even byte boundary
+    offset += offset % 2;
+    let at = offset + TERMINFO_COLORS_INDEX * num_size;
+    let bytes = data.get(at..at + num_size)?;
+    let value = if num_size == 2 {
+        i16::from_le_bytes([bytes[0], bytes[1]]) as i32
+    } else {
+        i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+    u32::try_from(value).ok()
+}
+
+/// Map a color onto the palette available in `mode`.
+pub fn quantize(color: Color, mode: ColorMode) -> Color {
+    if mode == ColorMode::Truecolor || color == Color::Reset {
+        return color;
+    }
+    let Some(rgb) = color_rgb(color) else {
+        return color;
+    };
+    match mode {
+        ColorMode::Truecolor => color,
+        ColorMode::Ansi256 => Color::Indexed(nearest_256(rgb)),
+        ColorMode::Ansi16 => ANSI_COLORS[nearest_16(rgb)],
+        ColorMode::Mono => Color::Reset,
+    }
+}
+
+/// Whether a foreground color, once quantized to 16 colors, is so much
+/// brighter than intended that it should be drawn with the DIM attribute.
+/// This keeps star twinkle and trail fades visible without truecolor.
+pub fn needs_dim(color: Color) -> bool {
+    let Some(rgb) = color_rgb(color) else {
+        return false;
+    };
+    let quantized = ANSI_PALETTE[nearest_16(rgb)];
+    luminance(rgb) < luminance(quantized) * 0.6
+}
+
+/// Attribute standing in for a foreground color's brightness in mono
+/// mode: bright colors turn bold, dark ones dim.
+pub fn mono_modifier(color: Color) -> Modifier {
+    match color_rgb(color).map(luminance) {
+        Some(l) if l >= 0.75 => Modifier::BOLD,
+        Some(l) if l < 0.35 => Modifier::DIM,
+        _ => Modifier::empty(),
+    }
+}
+
+/// Star and particle glyphs from heaviest to lightest.
+const MONO_GLYPHS: [char; 6] = ['✦', '*', '•', '∘', '·', '.'];
+
+/// Glyph standing in for a dim shade in mono mode: a star or particle dark
+/// enough to be drawn dim also steps down to the next lighter glyph, so faint
+/// stars look smaller as well as fainter. `None` keeps the glyph as it is.
+pub fn mono_glyph(symbol: &str, color: Color) -> Option<char> {
+    if mono_modifier(color) != Modifier::DIM {
+        return None;
+    }
+    let mut chars = symbol.chars();
+    let ch = chars.next().filter(|_| chars.next().is_none())?;
+    let i = MONO_GLYPHS.iter().position(|&g| g == ch)?;
+    MONO_GLYPHS.get(i + 1).copied()
+}
+
+/// `ratatui` colors in ANSI palette order.
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// Levels of each channel in the 6x6x6 color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Nearest xterm 256-color index, choosing between the color cube and the
+/// grayscale ramp. Indices 0-15 are skipped since terminals often remap them.
+fn nearest_256((r, g, b): (u8, u8, u8)) -> u8 {
+    let level = |v: u8| -> usize {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &l)| (l as i32 - v as i32).abs())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((avg.saturating_sub(8) + 5) / 10).min(23);
+    let gray_value = (8 + gray_step * 10) as u8;
+    let gray_index = 232 + gray_step as usize;
+
+    if distance((r, g, b), (gray_value, gray_value, gray_value)) < distance((r, g, b), cube) {
+        gray_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+/// Index into `ANSI_PALETTE` of the closest basic color.
+fn nearest_16(rgb: (u8, u8, u8)) -> usize {
+    ANSI_PALETTE
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &p)| distance(rgb, p))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Perceptually weighted squared RGB distance.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    2 * d(a.0, b.0) + 4 * d(a.1, b.1) + 3 * d(a.2, b.2)
+}
+
+fn luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    (0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_colorterm_wins() {
+        let mode = classify(Some("truecolor"), Some("xterm"), None, Some(8));
+        assert_eq!(mode, ColorMode::Truecolor);
+    }
+
+    #[test]
+    fn test_classify_terminfo() {
+        assert_eq!(
+            classify(None, Some("xterm-256color"), None, Some(256)),
+            ColorMode::Ansi256
+        );
+        assert_eq!(
+            classify(None, Some("linux"), None, Some(8)),
+            ColorMode::Ansi16
+        );
+        assert_eq!(
+            classify(None, Some("xterm-direct"), None, None),
+            ColorMode::Truecolor
+        );
+    }
+
+    #[test]
+    fn test_classify_fallbacks() {
+        assert_eq!(classify(None, Some("dumb"), None, None), ColorMode::Mono);
+        assert_eq!(
+            classify(None, Some("screen-256color"), None, None),
+            ColorMode::Ansi256
+        );
+        assert_eq!(classify(None, Some("vt100"), None, None), ColorMode::Ansi16);
+    }
+
+    #[test]
+    fn test_parse_terminfo_colors() {
+        // Legacy header: magic, 1-byte name, 1 bool, 14 numbers, no strings
+        let mut data = vec![0o32, 0o1, 1, 0, 1, 0, 14, 0, 0, 0, 0, 0];
+        data.push(0); // name terminator
+        data.push(1); // bool
+        data.extend(std::iter::repeat_n(0xff, 13 * 2));
+        data.extend(256i16.to_le_bytes());
+        assert_eq!(parse_terminfo_colors(&data), Some(256));
+        assert_eq!(parse_terminfo_colors(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn test_quantize_256() {
+        assert_eq!(
+            quantize(Color::Rgb(255, 0, 0), ColorMode::Ansi256),
+            Color::Indexed(196)
+        );
+        assert_eq!(
+            quantize(Color::Rgb(0, 255, 255), ColorMode::Ansi256),
+            Color::Indexed(51)
+        );
+        // Near-grays land on the grayscale ramp
+        assert_eq!(
+            quantize(Color::Rgb(128, 128, 130), ColorMode::Ansi256),
+            Color::Indexed(244)
+        );
+    }
+
+    #[test]
+    fn test_quantize_16() {
+        assert_eq!(
+            quantize(Color::Rgb(0, 255, 255), ColorMode::Ansi16),
+            Color::LightCyan
+        );
+        assert_eq!(
+            quantize(Color::Rgb(5, 0, 10), ColorMode::Ansi16),
+            Color::Black
+        );
+    }
+
+    #[test]
+    fn test_quantize_passthrough() {
+        let c = Color::Rgb(1, 2, 3);
+        assert_eq!(quantize(c, ColorMode::Truecolor), c);
+        assert_eq!(quantize(Color::Reset, ColorMode::Ansi16), Color::Reset);
+        assert_eq!(quantize(c, ColorMode::Mono), Color::Reset);
+    }
+
+    #[test]
+    fn test_mono_modifier() {
+        assert_eq!(mono_modifier(Color::Rgb(240, 240, 255)), Modifier::BOLD);
+        assert_eq!(mono_modifier(Color::Rgb(50, 55, 70)), Modifier::DIM);
+        assert_eq!(mono_modifier(Color::Rgb(140, 150, 180)), Modifier::empty());
+        assert_eq!(mono_modifier(Color::Reset), Modifier::empty());
+    }
+
+    #[test]
+    fn test_mono_glyph_lightens_dim_shades() {
+        let dim = Color::Rgb(50, 55, 70);
+        assert_eq!(mono_glyph("✦", dim), Some('*'));
+        assert_eq!(mono_glyph("·", dim), Some('.'));
+        assert_eq!(mono_glyph(".", dim), None);
+        assert_eq!(mono_glyph("█", dim), None);
+        assert_eq!(mono_glyph("✦", Color::Rgb(240, 240, 255)), None);
+    }
+
+    #[test]
+    fn test_needs_dim() {
+        assert!(!needs_dim(Color::Rgb(255, 255, 255)));
+        assert!(needs_dim(Color::Rgb(60, 70, 90)));
+    }
+}
@@ -0,0 +1,203 @@
+//! Non-interactive subcommands.
+
+use std::fmt::Write as _;
+use std::io::IsTerminal;
+
+use color_eyre::eyre::Result;
+use crossterm::style::{
+    Attribute, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor,
+};
+use ratatui::buffer::Buffer;
+use ratatui::style::Modifier;
+
+use crate::app::App;
+use crate::cli::{Args, Command, ThemeCommand};
+use crate::color::ColorMode;
+use crate::generate;
+use crate::render;
+use crate::theme::{format_color, themes_dir, user_theme_names, Theme, BUILTIN_THEMES};
+
+/// Size of one theme sample in `themes --preview`.
+const PREVIEW_WIDTH: u16 = 28;
+const PREVIEW_HEIGHT: u16 = 9;
+/// Columns between samples.
+const PREVIEW_GAP: u16 = 2;
+
+/// Run a subcommand to completion. `args` carries the global options, which
+/// previews honor (color mode, effects, star density and so on).
+pub fn run(command: &Command, args: &Args) -> Result<()> {
+    match command {
+        Command::Themes { preview: false } => {
+            list_themes();
+            Ok(())
+        }
+        Command::Themes { preview: true } => {
+            preview_themes(args);
+            Ok(())
+        }
+        Command::Theme {
+            action: ThemeCommand::Generate { color, name },
+        } => {
+            let theme = generate::theme_from_seed(name, *color);
+            println!(
+                "# Generated by stardial from {}",
+                format_color(*color).unwrap_or_default()
+            );
+            println!("# Save as ~/.config/stardial/themes/{name}.toml and use --theme {name}");
+            print!("{}", theme.to_toml());
+            Ok(())
+        }
+    }
+}
+
+fn list_themes() {
+    for name in BUILTIN_THEMES {
+        println!("{name:<16} built-in");
+    }
+    let dir = themes_dir();
+    for name in user_theme_names() {
+        match Theme::load(&name) {
+            Ok(_) => {
+                let path = dir.as_ref().map(|d| d.join(format!("{name}.toml")));
+                println!(
+                    "{name:<16} {}",
+                    path.map(|p| p.display().to_string()).unwrap_or_default()
+                );
+            }
+            Err(e) => println!("{name:<16} error: {e}"),
+        }
+    }
+}
+
+/// Print every theme as a small clock sample, as many per row as fit.
+fn preview_themes(args: &Args) {
+    let mut themes: Vec<Theme> = BUILTIN_THEMES.iter().map(|n| Theme::by_name(n)).collect();
+    for name in user_theme_names() {
+        match Theme::load(&name) {
+            Ok(theme) => themes.push(theme),
+            Err(e) => eprintln!("skipping theme `{name}`: {e}"),
+        }
+    }
+
+    let term_width = crossterm::terminal::size().map(|(w, _)| w).unwrap_or(80);
+    let per_row = ((term_width + PREVIEW_GAP) / (PREVIEW_WIDTH + PREVIEW_GAP)).max(1) as usize;
+
+    let mut sample_args = args.clone();
+    sample_args.format = "%H:%M".to_string();
+    sample_args.date = true;
+    sample_args.blink = false;
+    sample_args.size = 1;
+    sample_args.seed = Some(args.seed.unwrap_or(7));
+
+    // Piped output gets plain text, like mono
+    let mode = if std::io::stdout().is_terminal() {
+        args.color_mode().unwrap_or_else(ColorMode::detect)
+    } else {
+        ColorMode::Mono
+    };
+
+    for row in themes.chunks(per_row) {
+        let samples: Vec<(String, Buffer)> = row
+            .iter()
+            .map(|theme| {
+                let mut app = App::new(&sample_args, PREVIEW_WIDTH, PREVIEW_HEIGHT);
+                app.base_theme = app.overrides.apply(theme.clone());
+                app.theme = app.base_theme.clone();
+                let buf = render::render_to_buffer(&mut app, PREVIEW_WIDTH, PREVIEW_HEIGHT);
+                (theme.name.clone(), buf)
+            })
+            .collect();
+
+        for y in 0..PREVIEW_HEIGHT {
+            let mut line = String::new();
+            for (i, (_, buf)) in samples.iter().enumerate() {
+                if i > 0 {
+                    line.push_str(&" ".repeat(PREVIEW_GAP as usize));
+                }
+                push_ansi_row(&mut line, buf, y, mode);
+            }
+            println!("{line}");
+        }
+        let labels: Vec<String> = samples
+            .iter()
+            .map(|(name, _)| format!("{name:^width$}", width = PREVIEW_WIDTH as usize))
+            .collect();
+        println!("{}", labels.join(&" ".repeat(PREVIEW_GAP as usize)));
+        println!();
+    }
+}
+
+/// Append row `y` of `buf` as text with ANSI color and attribute escapes,
+/// emitting escapes only where the style changes. In mono mode the text is
+/// written without any escapes.
+fn push_ansi_row(out: &mut String, buf: &Buffer, y: u16, mode: ColorMode) {
+    if mode == ColorMode::Mono {
+        for x in 0..buf.area.width {
+            out.push_str(buf[(x, y)].symbol());
+        }
+        return;
+    }
+    let mut current = None;
+    for x in 0..buf.area.width {
+        let cell = &buf[(x, y)];
+        let style = (cell.fg, cell.bg, cell.modifier);
+        if current != Some(style) {
+            let _ = write!(
+                out,
+                "{}{}{}",
+                SetAttribute(Attribute::Reset),
+                SetForegroundColor(cell.fg.into()),
+                SetBackgroundColor(cell.bg.into()),
+            );
+            if cell.modifier.contains(Modifier::BOLD) {
+                let _ = write!(out, "{}", SetAttribute(Attribute::Bold));
+            }
+            if cell.modifier.contains(Modifier::DIM) {
+                let _ = write!(out, "{}", SetAttribute(Attribute::Dim));
+            }
+            current = Some(style);
+        }
+        out.push_str(cell.symbol());
+    }
+    let _ = write!(out, "{}{}", SetAttribute(Attribute::Reset), ResetColor);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::{Color, Style};
+
+    #[test]
+    fn test_ansi_row_groups_runs() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+        buf.set_string(0, 0, "ab", Style::default().fg(Color::Red));
+        buf.set_string(2, 0, "cd", Style::default().fg(Color::Blue));
+        let mut out = String::new();
+        push_ansi_row(&mut out, &buf, 0, ColorMode::Truecolor);
+        assert_eq!(
+            out.matches(&SetForegroundColor(Color::Red.into()).to_string())
+                .count(),
+            1
+        );
+        assert_eq!(
+            out.matches(&SetForegroundColor(Color::Blue.into()).to_string())
+                .count(),
+            1
+        );
+        let plain: String = out
+            .split('\x1b')
+            .map(|s| s.split_once('m').map_or(s, |(_, t)| t))
+            .collect();
+        assert_eq!(plain, "abcd");
+    }
+
+    #[test]
+    fn test_ansi_row_plain_in_mono() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 4, 1));
+        buf.set_string(0, 0, "abcd", Style::default().fg(Color::Red));
+        let mut out = String::new();
+        push_ansi_row(&mut out, &buf, 0, ColorMode::Mono);
+        assert_eq!(out, "abcd");
+    }
+}
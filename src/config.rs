@@ -0,0 +1,384 @@
+//! Config file support and live reloading.
+//!
+//! The config file holds default command-line options as TOML, one key per
+//! long flag (`fps = 24`, `theme = "nebula"`, `no-effects = true`,
+//! `theme-set = ["bg=#101020"]`). Options given on the command line win.
+//! Keys are turned back into flags and parsed by clap, so the file accepts
+//! exactly what the command line does.
+
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser};
+
+use crate::cli::Args;
+use crate::theme::{theme_path, themes_dir};
+
+/// Flags that make no sense in a config file.
+const CLI_ONLY: [&str; 3] = ["config", "help", "version"];
+
+/// How often [`FileWatcher`] looks at modification times.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `$XDG_CONFIG_HOME/stardial`, or `~/.config/stardial`.
+pub fn config_dir() -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".config")))?;
+    Some(config.join("stardial"))
+}
+
+/// Config file in use: `--config FILE`, or `config.toml` in [`config_dir`].
+pub fn config_path(args: &Args) -> Option<PathBuf> {
+    args.config
+        .clone()
+        .or_else(|| config_dir().map(|d| d.join("config.toml")))
+}
+
+/// Why [`load_args`] failed.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The command line itself, including `--help` and `--version`; at
+    /// startup, [`clap::Error::exit`] reports it the usual way
+    Cli(clap::Error),
+    /// The config file, prefixed with its path
+    Config(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Cli(e) => f.write_str(&clap_message(e)),
+            LoadError::Config(e) => f.write_str(e),
+        }
+    }
+}
+
+/// Parse `argv` merged with the config file.
+///
+/// Errors are returned rather than exiting, so a running clock can log them
+/// and keep its settings.
+pub fn load_args(argv: &[OsString]) -> Result<Args, LoadError> {
+    let cli = Args::command()
+        .try_get_matches_from(argv)
+        .map_err(LoadError::Cli)?;
+    let cli_args = Args::from_arg_matches(&cli).map_err(LoadError::Cli)?;
+    let Some(path) = config_path(&cli_args) else {
+        return Ok(cli_args);
+    };
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound && cli_args.config.is_none() => {
+            return Ok(cli_args);
+        }
+        Err(e) => {
+            return Err(LoadError::Config(format!(
+                "cannot read {}: {e}",
+                path.display()
+            )))
+        }
+    };
+
+    let in_file = |e: String| LoadError::Config(format!("{}: {e}", path.display()));
+    let flags = config_flags(&contents, &cli).map_err(in_file)?;
+    let mut merged: Vec<OsString> = argv.iter().take(1).cloned().collect();
+    merged.extend(flags.into_iter().map(OsString::from));
+    merged.extend(argv.iter().skip(1).cloned());
+    parse_merged(&merged).map_err(in_file)
+}
+
+/// Parse the combined config and command-line flags, keeping only the
+/// first line of clap's message (the rest is usage help for the terminal).
+fn parse_merged(argv: &[OsString]) -> Result<Args, String> {
+    Args::try_parse_from(argv).map_err(|e| clap_message(&e))
+}
+
+/// The first line of a clap error, without its `error: ` prefix.
+fn clap_message(e: &clap::Error) -> String {
+    let message = e.to_string();
+    let first = message.lines().next().unwrap_or_default();
+    first.trim_start_matches("error: ").to_string()
+}
+
+/// Translate a config file into flags, skipping options that `cli`
+/// already set on the command line.
+fn config_flags(contents: &str, cli: &ArgMatches) -> Result<Vec<String>, String> {
+    let table: toml::Table = contents
+        .parse()
+        .map_err(|e: toml::de::Error| e.message().to_string())?;
+    let command = Args::command();
+    let mut flags = Vec::new();
+    for (key, value) in &table {
+        let long = key.replace('_', "-");
+        let arg = command
+            .get_arguments()
+            .find(|a| a.get_long() == Some(long.as_str()) && !CLI_ONLY.contains(&long.as_str()))
+            .ok_or_else(|| format!("unknown key `{key}`"))?;
+        let on_cli = |id: &str| cli.value_source(id) == Some(ValueSource::CommandLine);
+        let conflicts = |a: &clap::Arg, b: &clap::Arg| {
+            command
+                .get_arg_conflicts_with(a)
+                .iter()
+                .any(|c| c.get_id() == b.get_id())
+        };
+        let overridden = on_cli(arg.get_id().as_str())
+            || command.get_arguments().any(|other| {
+                on_cli(other.get_id().as_str()) && (conflicts(arg, other) || conflicts(other, arg))
+            });
+        if overridden {
+            continue;
+        }
+        let values = match value {
+            toml::Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for value in values {
+            match value {
+                toml::Value::Boolean(true) => flags.push(format!("--{long}")),
+                toml::Value::Boolean(false) => {}
+                toml::Value::String(s) => flags.push(format!("--{long}={s}")),
+                toml::Value::Integer(n) => flags.push(format!("--{long}={n}")),
+                toml::Value::Float(f) => flags.push(format!("--{long}={f}")),
+                _ => return Err(format!("`{key}` must be a string, number or boolean")),
+            }
+        }
+    }
+    Ok(flags)
+}
+
+/// Decides when a running clock should re-read its settings: a watched file
+/// changed, or the process received `SIGHUP`.
+pub struct Reloader {
+    argv: Vec<OsString>,
+    watcher: FileWatcher,
+    hangup: Arc<AtomicBool>,
+}
+
+impl Reloader {
+    pub fn new(argv: Vec<OsString>, args: &Args) -> std::io::Result<Self> {
+        let hangup = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&hangup))?;
+        Ok(Self {
+            argv,
+            watcher: FileWatcher::new(args),
+            hangup,
+        })
+    }
+
+    /// New settings when a reload is due, `None` otherwise.
+    pub fn poll(&mut self) -> Option<Result<Args, String>> {
+        let changed = self.watcher.changed();
+        let hangup = self.hangup.swap(false, Ordering::Relaxed);
+        if !changed && !hangup {
+            return None;
+        }
+        let result = load_args(&self.argv).map_err(|e| e.to_string());
+        if let Ok(ref args) = result {
+            // The set of theme files in use may have changed too
+            self.watcher = FileWatcher::new(args);
+        }
+        Some(result)
+    }
+}
+
+/// Polls the config file and theme files for changes.
+///
+/// Modification times are compared at most once per [`POLL_INTERVAL`], which
+/// is plenty for a human saving a file and costs nothing between checks.
+pub struct FileWatcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>,
+    last_check: Instant,
+}
+
+impl FileWatcher {
+    /// Watch everything that `args` reads: the config file, the active theme
+    /// files and the themes directory.
+    pub fn new(args: &Args) -> Self {
+        let mut paths: Vec<PathBuf> = config_path(args).into_iter().collect();
+        paths.extend(themes_dir());
+        let themes = [&args.theme, &args.day_theme, &args.night_theme];
+        paths.extend(themes.into_iter().filter_map(|name| theme_path(name)));
+        Self::watch(paths)
+    }
+
+    /// Watch files and directories in `paths`.
+    pub fn watch(mut paths: Vec<PathBuf>) -> Self {
+        paths.sort();
+        paths.dedup();
+        let stamps = Self::stamps(&paths);
+        Self {
+            paths,
+            stamps,
+            last_check: Instant::now(),
+        }
+    }
+
+    /// True once after any watched file was created, changed or removed.
+    pub fn changed(&mut self) -> bool {
+        if self.last_check.elapsed() < POLL_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+        let stamps = Self::stamps(&self.paths);
+        if stamps == self.stamps {
+            return false;
+        }
+        self.stamps = stamps;
+        true
+    }
+
+    /// Latest modification time under each path; directories count their
+    /// direct children so saving a theme file is noticed.
+    fn stamps(paths: &[PathBuf]) -> Vec<Option<SystemTime>> {
+        let mtime = |p: &std::path::Path| p.metadata().and_then(|m| m.modified()).ok();
+        paths
+            .iter()
+            .map(|path| {
+                let own = mtime(path);
+                if !path.is_dir() {
+                    return own;
+                }
+                std::fs::read_dir(path)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|e| mtime(&e.ok()?.path()))
+                    .chain(own)
+                    .max()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flags(contents: &str, cli: &[&str]) -> Result<Vec<String>, String> {
+        let matches = Args::command().get_matches_from(cli);
+        config_flags(contents, &matches)
+    }
+
+    #[test]
+    fn test_config_flags() {
+        let contents = r##"
+            fps = 24
+            theme = "nebula"
+            no_effects = true
+            blink = false
+            transition = 0.5
+            theme-set = ["bg=#101020", "star_dim=gray"]
+        "##;
+        let got = flags(contents, &["stardial"]).unwrap();
+        let mut got_sorted = got.clone();
+        got_sorted.sort();
+        assert_eq!(
+            got_sorted,
+            vec![
+                "--fps=24",
+                "--no-effects",
+                "--theme-set=bg=#101020",
+                "--theme-set=star_dim=gray",
+                "--theme=nebula",
+                "--transition=0.5",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_command_line_wins() {
+        let got = flags("fps = 24\nstars = 80\n", &["stardial", "--fps", "10"]).unwrap();
+        assert_eq!(got, vec!["--stars=80"]);
+        let got = flags("theme = \"luna\"", &["stardial", "--theme-from", "red"]).unwrap();
+        assert!(
+            got.is_empty(),
+            "conflicting config keys yield to the command line"
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_cli_only_keys() {
+        assert!(flags("sparkle = true", &["stardial"])
+            .unwrap_err()
+            .contains("unknown key"));
+        assert!(flags("config = \"x.toml\"", &["stardial"]).is_err());
+        assert!(flags("fps = { a = 1 }", &["stardial"]).is_err());
+    }
+
+    #[test]
+    fn test_command_line_errors_are_returned() {
+        let argv: Vec<OsString> = ["stardial", "--fps=999"].map(OsString::from).into();
+        let err = load_args(&argv).unwrap_err();
+        assert!(matches!(err, LoadError::Cli(_)));
+        assert!(err.to_string().contains("--fps"));
+    }
+
+    /// A fresh, empty directory under the system temp dir.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stardial-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Check for changes now, without waiting out the poll interval.
+    fn poll(watcher: &mut FileWatcher) -> bool {
+        watcher.last_check -= POLL_INTERVAL;
+        watcher.changed()
+    }
+
+    /// Set the modification time of `path` to `secs` seconds from now.
+    fn touch(path: &std::path::Path, secs: u64) {
+        let file = std::fs::File::options().append(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(secs))
+            .unwrap();
+    }
+
+    #[test]
+    fn test_watcher_notices_changed_file() {
+        let dir = temp_dir("watch-file");
+        let file = dir.join("config.toml");
+        std::fs::write(&file, "fps = 24\n").unwrap();
+        let mut watcher = FileWatcher::watch(vec![file.clone()]);
+        assert!(!poll(&mut watcher), "nothing changed yet");
+        touch(&file, 10);
+        assert!(!watcher.changed(), "checks wait for the poll interval");
+        assert!(poll(&mut watcher));
+        assert!(!poll(&mut watcher), "a change is reported once");
+        std::fs::remove_file(&file).unwrap();
+        assert!(poll(&mut watcher), "removing a file is a change");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_watcher_notices_files_in_directory() {
+        let dir = temp_dir("watch-dir");
+        let mut watcher = FileWatcher::watch(vec![dir.clone()]);
+        let theme = dir.join("sakura.toml");
+        std::fs::write(&theme, "accent = \"pink\"\n").unwrap();
+        touch(&theme, 10);
+        assert!(poll(&mut watcher), "a new theme file is a change");
+        touch(&theme, 20);
+        assert!(poll(&mut watcher), "saving a theme file is a change");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merged_args_are_validated() {
+        let merged: Vec<OsString> = ["stardial", "--fps=999"].map(OsString::from).into();
+        assert!(parse_merged(&merged).is_err());
+        let merged: Vec<OsString> = ["stardial", "--fps=12", "--date"]
+            .map(OsString::from)
+            .into();
+        let args = parse_merged(&merged).unwrap();
+        assert_eq!(args.fps, 12);
+        assert!(args.date);
+    }
+}
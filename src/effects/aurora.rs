@@ -0,0 +1,198 @@
+//! Aurora and nebula glow: slowly shifting noise painted into the cell
+//! backgrounds, under everything else.
+
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use super::noise::ValueNoise;
+use super::{Effect, RenderContext};
+use crate::color::ColorMode;
+use crate::theme::lerp_color;
+
+/// Strongest blend from the theme background toward the aurora color.
+const MAX_STRENGTH: f32 = 0.35;
+
+/// Shape of the glow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuroraStyle {
+    /// Curtains of light hanging across the upper sky
+    Bands,
+    /// Drifting clouds of gas
+    Clouds,
+}
+
+/// The aurora / nebula background effect.
+#[derive(Debug, Clone)]
+pub struct Aurora {
+    style: AuroraStyle,
+    noise: ValueNoise,
+    /// Animation time in seconds
+    time: f32,
+}
+
+impl Aurora {
+    pub fn new(style: AuroraStyle, seed: Option<u64>) -> Self {
+        let seed = seed.map_or_else(|| fastrand::u64(..), |s| s.wrapping_add(0xA0B0));
+        Self {
+            style,
+            noise: ValueNoise::new(seed),
+            time: 0.0,
+        }
+    }
+
+    /// Glow strength (0.0-1.0) of every cell in one column, plus how far
+    /// each cell sits toward the `secondary` fringe (0.0-1.0).
+    fn bands_column(&self, x: f32, height: u16, out: &mut Vec<(f32, f32)>) {
+        let t = self.time;
+        let h = height as f32;
+        let n = &self.noise;
+        // The curtain's lower edge wanders across the upper half of the sky
+        let edge = h * (0.25 + 0.3 * n.fbm(x * 0.02 + t * 0.03, 0.5, 2));
+        let thickness = h * (0.1 + 0.12 * n.sample(x * 0.05 - t * 0.05, 7.3)) + 1.0;
+        // Vertical rays and slow brightening/fading along the curtain
+        let rays = 0.5 + 0.5 * n.sample(x * 0.35 + t * 0.25, 13.1);
+        let glow = (n.fbm(x * 0.015 - t * 0.02, 3.3, 2) * 1.6 - 0.3).clamp(0.0, 1.0);
+
+        out.clear();
+        for y in 0..height {
+            // Rays reach far above the lower edge but fade fast below it
+            let d = (y as f32 - edge) / thickness;
+            let falloff = if d < 0.0 {
+                (-(d * 0.45).powi(2)).exp()
+            } else {
+                (-(d * 2.0).powi(2)).exp()
+            };
+            let fringe = (-d * 0.4).clamp(0.0, 1.0);
+            out.push((falloff * rays * glow, fringe));
+        }
+    }
+
+    /// Glow strength and color mix for one cell of cloud.
+    fn clouds_cell(&self, x: f32, y: f32) -> (f32, f32) {
+        let t = self.time;
+        let n = &self.noise;
+        // Rows are about twice as tall as columns are wide
+        let density = n.fbm(x * 0.045 + t * 0.015, y * 0.09 - t * 0.01, 4);
+        let strength = smoothstep(0.42, 0.8, density);
+        let mix = n.fbm(x * 0.02 - t * 0.01 + 40.0, y * 0.05, 2);
+        (strength, mix)
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+impl Effect for Aurora {
+    fn z_order(&self) -> i32 {
+        -30
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.time += dt;
+    }
+
+    fn resize(&mut self, _width: u16, _height: u16) {}
+
+    /// Tint cell backgrounds toward the theme's `aurora` color, shading to
+    /// `secondary` at the fringes. A transparent background has no color to
+    /// blend with, and 16 colors or mono cannot show a subtle tint, so
+    /// nothing is drawn in those modes.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        if ctx.transparent || matches!(ctx.color_mode, ColorMode::Ansi16 | ColorMode::Mono) {
+            return;
+        }
+        let theme = ctx.theme;
+        let mut column = Vec::with_capacity(area.height as usize);
+        for x in area.x..area.x + area.width {
+            if self.style == AuroraStyle::Bands {
+                self.bands_column(x as f32, area.height, &mut column);
+            }
+            for row in 0..area.height {
+                let (strength, mix) = match self.style {
+                    AuroraStyle::Bands => column[row as usize],
+                    AuroraStyle::Clouds => self.clouds_cell(x as f32, row as f32),
+                };
+                if strength < 0.01 {
+                    continue;
+                }
+                let tint = lerp_color(theme.aurora, theme.secondary, mix);
+                if let Some(cell) = buf.cell_mut((x, area.y + row)) {
+                    cell.bg = lerp_color(theme.bg, tint, strength * MAX_STRENGTH);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{ClearZone, DigitMask};
+    use crate::theme::Theme;
+
+    fn render(aurora: &mut Aurora, ctx: &RenderContext) -> Buffer {
+        let area = Rect::new(0, 0, 60, 20);
+        let mut buf = Buffer::empty(area);
+        aurora.render(&mut buf, area, ctx);
+        buf
+    }
+
+    fn ctx(theme: &Theme) -> RenderContext<'_> {
+        RenderContext {
+            theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::default(),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        }
+    }
+
+    #[test]
+    fn test_deterministic_with_seed() {
+        let theme = Theme::nebula();
+        for style in [AuroraStyle::Bands, AuroraStyle::Clouds] {
+            let mut a = Aurora::new(style, Some(9));
+            let mut b = Aurora::new(style, Some(9));
+            a.tick(3.0);
+            b.tick(3.0);
+            assert_eq!(render(&mut a, &ctx(&theme)), render(&mut b, &ctx(&theme)));
+        }
+    }
+
+    #[test]
+    fn test_paints_some_backgrounds() {
+        let theme = Theme::void();
+        for style in [AuroraStyle::Bands, AuroraStyle::Clouds] {
+            let mut glow = None;
+            for seed in 0..10 {
+                let buf = render(&mut Aurora::new(style, Some(seed)), &ctx(&theme));
+                if buf
+                    .content()
+                    .iter()
+                    .any(|c| c.bg != ratatui::style::Color::Reset)
+                {
+                    glow = Some(seed);
+                    break;
+                }
+            }
+            assert!(glow.is_some(), "{style:?} should tint some cells");
+        }
+    }
+
+    #[test]
+    fn test_skipped_when_transparent() {
+        let theme = Theme::void();
+        let ctx = RenderContext {
+            transparent: true,
+            ..ctx(&theme)
+        };
+        let buf = render(&mut Aurora::new(AuroraStyle::Clouds, Some(1)), &ctx);
+        assert!(buf
+            .content()
+            .iter()
+            .all(|c| c.bg == ratatui::style::Color::Reset));
+    }
+}
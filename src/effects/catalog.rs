@@ -0,0 +1,239 @@
+//! A small bundled catalog of bright constellation figures.
+//!
+//! Positions are J2000 right ascension (hours) and declination (degrees);
+//! precession over a few decades moves them far less than one cell.
+
+/// A catalog star.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CatalogStar {
+    /// Right ascension in hours (0-24)
+    pub ra: f64,
+    /// Declination in degrees (-90 to 90)
+    pub dec: f64,
+    /// Visual magnitude (lower is brighter)
+    pub mag: f32,
+}
+
+/// A constellation figure: its stars and the lines joining them.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Constellation {
+    pub name: &'static str,
+    pub stars: &'static [CatalogStar],
+    /// Pairs of indices into `stars`
+    pub lines: &'static [(usize, usize)],
+}
+
+const fn star(ra: f64, dec: f64, mag: f32) -> CatalogStar {
+    CatalogStar { ra, dec, mag }
+}
+
+pub const CONSTELLATIONS: &[Constellation] = &[
+    Constellation {
+        name: "Orion",
+        stars: &[
+            star(5.919, 7.41, 0.5),  // Betelgeuse
+            star(5.419, 6.35, 1.6),  // Bellatrix
+            star(5.533, -0.30, 2.2), // Mintaka
+            star(5.603, -1.20, 1.7), // Alnilam
+            star(5.679, -1.94, 1.8), // Alnitak
+            star(5.796, -9.67, 2.1), // Saiph
+            star(5.242, -8.20, 0.1), // Rigel
+            star(5.585, 9.93, 3.4),  // Meissa
+        ],
+        lines: &[
+            (7, 0),
+            (7, 1),
+            (0, 1),
+            (0, 4),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (2, 6),
+        ],
+    },
+    Constellation {
+        name: "Taurus",
+        stars: &[
+            star(4.599, 16.51, 0.9), // Aldebaran
+            star(5.438, 28.61, 1.7), // Elnath
+            star(5.627, 21.14, 3.0), // Tianguan
+            star(4.330, 15.63, 3.7), // Prima Hyadum
+            star(4.382, 17.54, 3.8), // Secunda Hyadum
+            star(4.477, 19.18, 3.5), // Ain
+            star(4.011, 12.49, 3.4), // Lambda Tauri
+            star(3.791, 24.11, 2.9), // Alcyone
+        ],
+        lines: &[(6, 3), (3, 0), (0, 2), (3, 4), (4, 5), (5, 1)],
+    },
+    Constellation {
+        name: "Gemini",
+        stars: &[
+            star(7.577, 31.89, 1.6), // Castor
+            star(7.755, 28.03, 1.1), // Pollux
+            star(6.629, 16.40, 1.9), // Alhena
+            star(6.732, 25.13, 3.0), // Mebsuta
+            star(6.383, 22.51, 2.9), // Tejat
+            star(7.335, 21.98, 3.5), // Wasat
+        ],
+        lines: &[(0, 1), (0, 3), (3, 4), (1, 5), (5, 2)],
+    },
+    Constellation {
+        name: "Canis Major",
+        stars: &[
+            star(6.752, -16.72, -1.5), // Sirius
+            star(6.378, -17.96, 2.0),  // Mirzam
+            star(6.977, -28.97, 1.5),  // Adhara
+            star(7.140, -26.39, 1.8),  // Wezen
+            star(7.402, -29.30, 2.4),  // Aludra
+        ],
+        lines: &[(1, 0), (0, 3), (3, 2), (3, 4)],
+    },
+    Constellation {
+        name: "Auriga",
+        stars: &[
+            star(5.278, 46.00, 0.1), // Capella
+            star(5.992, 44.95, 1.9), // Menkalinan
+            star(5.995, 37.21, 2.6), // Mahasim
+            star(4.950, 33.17, 2.7), // Hassaleh
+            star(5.033, 43.82, 3.0), // Almaaz
+        ],
+        lines: &[(0, 1), (1, 2), (3, 4), (4, 0)],
+    },
+    Constellation {
+        name: "Ursa Major",
+        stars: &[
+            star(11.062, 61.75, 1.8), // Dubhe
+            star(11.031, 56.38, 2.4), // Merak
+            star(11.897, 53.69, 2.4), // Phecda
+            star(12.257, 57.03, 3.3), // Megrez
+            star(12.900, 55.96, 1.8), // Alioth
+            star(13.399, 54.93, 2.2), // Mizar
+            star(13.792, 49.31, 1.9), // Alkaid
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 0), (3, 4), (4, 5), (5, 6)],
+    },
+    Constellation {
+        name: "Cassiopeia",
+        stars: &[
+            star(0.153, 59.15, 2.3), // Caph
+            star(0.675, 56.54, 2.2), // Schedar
+            star(0.945, 60.72, 2.2), // Navi
+            star(1.430, 60.24, 2.7), // Ruchbah
+            star(1.906, 63.67, 3.4), // Segin
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 4)],
+    },
+    Constellation {
+        name: "Leo",
+        stars: &[
+            star(10.139, 11.97, 1.4), // Regulus
+            star(10.122, 16.76, 3.5), // Eta Leonis
+            star(10.333, 19.84, 2.0), // Algieba
+            star(10.278, 23.42, 3.4), // Adhafera
+            star(9.879, 26.01, 3.9),  // Rasalas
+            star(9.764, 23.77, 3.0),  // Ras Elased
+            star(11.235, 20.52, 2.6), // Zosma
+            star(11.237, 15.43, 3.3), // Chertan
+            star(11.818, 14.57, 2.1), // Denebola
+        ],
+        lines: &[
+            (0, 1),
+            (1, 2),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (2, 6),
+            (6, 8),
+            (8, 7),
+            (7, 0),
+        ],
+    },
+    Constellation {
+        name: "Lyra",
+        stars: &[
+            star(18.616, 38.78, 0.0), // Vega
+            star(18.746, 37.61, 4.3), // Zeta Lyrae
+            star(18.835, 33.36, 3.5), // Sheliak
+            star(18.982, 32.69, 3.3), // Sulafat
+            star(18.908, 36.90, 4.3), // Delta Lyrae
+        ],
+        lines: &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 1)],
+    },
+    Constellation {
+        name: "Cygnus",
+        stars: &[
+            star(20.690, 45.28, 1.3), // Deneb
+            star(20.370, 40.26, 2.2), // Sadr
+            star(19.512, 27.96, 3.1), // Albireo
+            star(20.770, 33.97, 2.5), // Aljanah
+            star(19.750, 45.13, 2.9), // Fawaris
+        ],
+        lines: &[(0, 1), (1, 2), (3, 1), (1, 4)],
+    },
+    Constellation {
+        name: "Scorpius",
+        stars: &[
+            star(16.490, -26.43, 1.0), // Antares
+            star(16.006, -22.62, 2.3), // Dschubba
+            star(16.091, -19.81, 2.6), // Acrab
+            star(15.981, -26.11, 2.9), // Fang
+            star(16.598, -28.22, 2.8), // Paikauhale
+            star(16.836, -34.29, 2.3), // Larawag
+            star(16.864, -38.05, 3.0), // Xamidimura
+            star(16.910, -42.36, 3.6), // Zeta Scorpii
+            star(17.203, -43.24, 3.3), // Eta Scorpii
+            star(17.622, -43.00, 1.9), // Sargas
+            star(17.793, -40.13, 3.0), // Iota Scorpii
+            star(17.708, -39.03, 2.4), // Girtab
+            star(17.560, -37.10, 1.6), // Shaula
+        ],
+        lines: &[
+            (2, 1),
+            (3, 1),
+            (1, 0),
+            (0, 4),
+            (4, 5),
+            (5, 6),
+            (6, 7),
+            (7, 8),
+            (8, 9),
+            (9, 10),
+            (10, 11),
+            (11, 12),
+        ],
+    },
+    Constellation {
+        name: "Crux",
+        stars: &[
+            star(12.443, -63.10, 0.8), // Acrux
+            star(12.795, -59.69, 1.3), // Mimosa
+            star(12.519, -57.11, 1.6), // Gacrux
+            star(12.252, -58.75, 2.8), // Imai
+        ],
+        lines: &[(0, 2), (1, 3)],
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catalog_is_well_formed() {
+        for figure in CONSTELLATIONS {
+            for &(a, b) in figure.lines {
+                assert!(
+                    a < figure.stars.len() && b < figure.stars.len(),
+                    "{}",
+                    figure.name
+                );
+                assert_ne!(a, b, "{}", figure.name);
+            }
+            for s in figure.stars {
+                assert!((0.0..24.0).contains(&s.ra), "{}", figure.name);
+                assert!((-90.0..=90.0).contains(&s.dec), "{}", figure.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! Real constellations drawn faintly behind the clock.
+//!
+//! With an observer location the view is centered on the zenith and turns
+//! with local sidereal time, so figures rise in the east and set in the
+//! west over the night. Without one the screen shows a fixed patch of sky.
+
+use chrono::{DateTime, Utc};
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use super::catalog::CONSTELLATIONS;
+use super::starfield::streak_char;
+use super::{Effect, RenderContext};
+use crate::astro::{self, Location};
+use crate::theme::lerp_color;
+
+/// Degrees of sky spanned by the screen height.
+const FIELD_HEIGHT: f64 = 70.0;
+
+/// Sky at the screen center without a location (RA, Dec in degrees): the
+/// winter hexagon around Orion.
+const STATIC_CENTER: (f64, f64) = (90.0, 12.0);
+
+/// How often the sky is re-projected while turning, in seconds. It moves
+/// a quarter of a degree a minute, far less than a cell.
+const REFRESH_SECONDS: f32 = 1.0;
+
+/// Blend of the connecting lines from the background toward `star_dim`.
+const LINE_STRENGTH: f32 = 0.6;
+
+/// The patch of sky on screen: centered on the zenith at a moment in time
+/// for an observer, or on a fixed view without one.
+#[derive(Debug, Clone, Copy)]
+pub struct SkyView {
+    /// (RA, Dec) in degrees at the screen center
+    center: (f64, f64),
+    width: u16,
+    height: u16,
+}
+
+impl SkyView {
+    pub fn new(location: Option<Location>, now: DateTime<Utc>, width: u16, height: u16) -> Self {
+        let center = match location {
+            Some(loc) => (astro::local_sidereal_degrees(now, loc.lon), loc.lat),
+            None => STATIC_CENTER,
+        };
+        Self {
+            center,
+            width,
+            height,
+        }
+    }
+
+    /// Screen position (columns, rows) of right ascension `ra` (hours) and
+    /// declination `dec` (degrees), or `None` below the horizon.
+    ///
+    /// Stereographic projection around the screen center, north up and east
+    /// to the left as seen looking up. Points may fall outside the screen.
+    pub fn project(&self, ra: f64, dec: f64) -> Option<(f32, f32)> {
+        let (ra0, dec0) = self.center;
+        let (sin_d0, cos_d0) = dec0.to_radians().sin_cos();
+        let (sin_d, cos_d) = dec.to_radians().sin_cos();
+        let h = (ra * 15.0 - ra0).to_radians();
+        let cos_c = sin_d0 * sin_d + cos_d0 * cos_d * h.cos();
+        // More than 90 degrees from the zenith: below the horizon
+        if cos_c <= 0.0 {
+            return None;
+        }
+        let k = 2.0 / (1.0 + cos_c);
+        let x = k * cos_d * h.sin();
+        let y = k * (cos_d0 * sin_d - sin_d0 * cos_d * h.cos());
+        let scale = self.height as f64 / FIELD_HEIGHT.to_radians();
+        let (cx, cy) = (self.width as f64 / 2.0, self.height as f64 / 2.0);
+        // Rows are about twice as tall as columns are wide
+        Some(((cx - x * scale * 2.0) as f32, (cy - y * scale) as f32))
+    }
+}
+
+/// Constellation figures projected onto the screen.
+#[derive(Debug, Clone)]
+pub struct Constellations {
+    location: Option<Location>,
+    width: u16,
+    height: u16,
+    /// Screen position of every catalog star, per figure; `None` when the
+    /// star is below the horizon
+    points: Vec<Vec<Option<(f32, f32)>>>,
+    /// Seconds until the next re-projection
+    refresh: f32,
+}
+
+impl Constellations {
+    pub fn new(width: u16, height: u16, location: Option<Location>) -> Self {
+        let mut constellations = Self {
+            location,
+            width,
+            height,
+            points: Vec::new(),
+            refresh: REFRESH_SECONDS,
+        };
+        constellations.project(Utc::now());
+        constellations
+    }
+
+    /// Place every catalog star for the sky at `now`.
+    fn project(&mut self, now: DateTime<Utc>) {
+        let view = SkyView::new(self.location, now, self.width, self.height);
+        self.points = CONSTELLATIONS
+            .iter()
+            .map(|figure| {
+                figure
+                    .stars
+                    .iter()
+                    .map(|s| view.project(s.ra, s.dec))
+                    .collect()
+            })
+            .collect();
+    }
+
+    /// Glyph for a catalog star by magnitude.
+    fn star_char(mag: f32) -> char {
+        match mag {
+            m if m < 1.0 => '✦',
+            m if m < 2.5 => '•',
+            _ => '·',
+        }
+    }
+}
+
+impl Effect for Constellations {
+    fn z_order(&self) -> i32 {
+        -15
+    }
+
+    fn tick(&mut self, dt: f32) {
+        if self.location.is_none() {
+            return;
+        }
+        self.refresh -= dt;
+        if self.refresh <= 0.0 {
+            self.refresh = REFRESH_SECONDS;
+            self.project(Utc::now());
+        }
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.project(Utc::now());
+    }
+
+    /// Lines first, only over empty cells so the procedural stars show
+    /// through them, then the catalog stars on top.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        let theme = ctx.theme;
+        let in_area = |x: f32, y: f32| {
+            x >= area.x as f32
+                && y >= area.y as f32
+                && x < (area.x + area.width) as f32
+                && y < (area.y + area.height) as f32
+        };
+
+        let line_color = lerp_color(theme.bg, theme.star_dim, LINE_STRENGTH);
+        for (figure, points) in CONSTELLATIONS.iter().zip(&self.points) {
+            for &(a, b) in figure.lines {
+                let (Some(from), Some(to)) = (points[a], points[b]) else {
+                    continue;
+                };
+                let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+                let ch = streak_char(dx, dy);
+                let steps = dx.abs().max(dy.abs()).ceil() as usize;
+                // The end cells belong to the stars
+                for i in 1..steps {
+                    let t = i as f32 / steps as f32;
+                    let (x, y) = (from.0 + dx * t, from.1 + dy * t);
+                    if !in_area(x, y) {
+                        continue;
+                    }
+                    if let Some(cell) = buf.cell_mut((x as u16, y as u16)) {
+                        if cell.symbol() == " " {
+                            cell.set_char(ch).set_fg(line_color);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (figure, points) in CONSTELLATIONS.iter().zip(&self.points) {
+            for (star, point) in figure.stars.iter().zip(points) {
+                let Some((x, y)) = *point else { continue };
+                if !in_area(x, y) {
+                    continue;
+                }
+                let brightness = ((4.0 - star.mag) / 4.0).clamp(0.3, 1.0);
+                let color = lerp_color(theme.star_dim, theme.star_bright, brightness);
+                if let Some(cell) = buf.cell_mut((x as u16, y as u16)) {
+                    cell.set_char(Self::star_char(star.mag)).set_fg(color);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorMode;
+    use crate::effects::{ClearZone, DigitMask};
+    use crate::theme::Theme;
+    use chrono::TimeZone;
+
+    fn on_screen(c: &Constellations) -> usize {
+        c.points
+            .iter()
+            .flatten()
+            .flatten()
+            .filter(|(x, y)| *x >= 0.0 && *y >= 0.0 && *x < c.width as f32 && *y < c.height as f32)
+            .count()
+    }
+
+    #[test]
+    fn test_static_view_shows_orion() {
+        let c = Constellations::new(100, 30, None);
+        assert!(on_screen(&c) > 10);
+        let orion = &c.points[0];
+        assert!(orion.iter().all(|p| p.is_some()));
+    }
+
+    #[test]
+    fn test_sky_turns_with_sidereal_time() {
+        let tokyo = Location {
+            lat: 35.7,
+            lon: 139.7,
+        };
+        let mut c = Constellations::new(100, 30, Some(tokyo));
+        let t = Utc.with_ymd_and_hms(2024, 1, 15, 13, 0, 0).unwrap();
+        c.project(t);
+        let before = c.points.clone();
+        c.project(t + chrono::Duration::hours(1));
+        assert_ne!(before, c.points);
+    }
+
+    #[test]
+    fn test_horizon_hides_stars() {
+        let pole = Location {
+            lat: 89.0,
+            lon: 0.0,
+        };
+        let mut c = Constellations::new(100, 30, Some(pole));
+        c.project(Utc.with_ymd_and_hms(2024, 6, 1, 0, 0, 0).unwrap());
+        let crux = CONSTELLATIONS
+            .iter()
+            .position(|f| f.name == "Crux")
+            .unwrap();
+        assert!(c.points[crux].iter().all(|p| p.is_none()));
+        let big_dipper = CONSTELLATIONS
+            .iter()
+            .position(|f| f.name == "Ursa Major")
+            .unwrap();
+        assert!(c.points[big_dipper].iter().all(|p| p.is_some()));
+    }
+
+    #[test]
+    fn test_render_draws_stars_and_lines() {
+        let mut c = Constellations::new(100, 30, None);
+        let theme = Theme::void();
+        let ctx = RenderContext {
+            theme: &theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::default(),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        };
+        let area = Rect::new(0, 0, 100, 30);
+        let mut buf = Buffer::empty(area);
+        c.render(&mut buf, area, &ctx);
+        let symbols: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(symbols.contains('✦'));
+        assert!(symbols.contains(['─', '│', '╲', '╱']));
+    }
+}
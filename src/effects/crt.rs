@@ -0,0 +1,385 @@
+//! CRT post-processing: the finishing passes that make the whole frame look
+//! like it is shown on an old tube, drawn after everything else (scanlines
+//! included).
+
+use fastrand::Rng;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::{Effect, RenderContext};
+use crate::color::ColorMode;
+use crate::theme::lerp_color;
+
+/// A single CRT pass, selected with `--crt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CrtPass {
+    /// Darken toward the edges and corners of a curved screen
+    Vignette,
+    /// Subtle random brightness dips
+    Flicker,
+    /// Fading ghosts of what was drawn in previous frames
+    Persistence,
+    /// Red and blue fringes on the edges of the digits
+    Chromatic,
+}
+
+/// Passes enabled when `--crt` is not given.
+pub const DEFAULT_PASSES: &str = "vignette,flicker,persistence,chromatic";
+
+/// Darkening in the corners of the screen.
+const VIGNETTE_STRENGTH: f32 = 0.6;
+
+/// Deepest brightness dip of the flicker.
+const FLICKER_DEPTH: f32 = 0.12;
+
+/// Seconds each flicker level is held.
+const FLICKER_STEP: f32 = 0.05;
+
+/// Seconds for a phosphor ghost to fade to about a third.
+const PERSISTENCE_SECONDS: f32 = 0.12;
+
+/// Brightness of a fresh ghost relative to the glyph that left it.
+const GHOST_STRENGTH: f32 = 0.5;
+
+/// Ghosts fainter than this are dropped.
+const GHOST_MIN: f32 = 0.05;
+
+/// Fringe colors to the left (red) and right (blue) of digit strokes.
+const FRINGE_LEFT: Color = Color::Rgb(255, 40, 40);
+const FRINGE_RIGHT: Color = Color::Rgb(40, 80, 255);
+
+/// Blend of the fringe into the neighbouring background, and of the
+/// stroke's own edge color.
+const FRINGE_STRENGTH: f32 = 0.25;
+const EDGE_SHIFT: f32 = 0.15;
+
+const BLACK: Color = Color::Rgb(0, 0, 0);
+
+/// What the phosphor still shows of a cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Phosphor {
+    ch: char,
+    fg: Color,
+    /// Remaining brightness (0.0-1.0)
+    level: f32,
+}
+
+const DARK: Phosphor = Phosphor {
+    ch: ' ',
+    fg: Color::Reset,
+    level: 0.0,
+};
+
+/// The CRT post-processing effect.
+#[derive(Debug)]
+pub struct Crt {
+    vignette: bool,
+    flicker: bool,
+    persistence: bool,
+    chromatic: bool,
+    rng: Rng,
+    /// Current flicker dip (0.0-`FLICKER_DEPTH`)
+    dip: f32,
+    /// Seconds until the next flicker level
+    dip_timer: f32,
+    /// Glow left on the screen by earlier frames, row by row, refreshed
+    /// as each frame is drawn
+    phosphor: Vec<Phosphor>,
+}
+
+impl Crt {
+    pub fn new(passes: &[CrtPass], seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(s) => Rng::with_seed(s.wrapping_add(0xC27)),
+            None => Rng::new(),
+        };
+        Self {
+            vignette: passes.contains(&CrtPass::Vignette),
+            flicker: passes.contains(&CrtPass::Flicker),
+            persistence: passes.contains(&CrtPass::Persistence),
+            chromatic: passes.contains(&CrtPass::Chromatic),
+            rng,
+            dip: 0.0,
+            dip_timer: 0.0,
+            phosphor: Vec::new(),
+        }
+    }
+
+    /// Darkening (0.0-1.0) of the cell at `(x, y)` within `area`.
+    fn vignette_at(area: Rect, x: u16, y: u16) -> f32 {
+        let half_w = area.width as f32 / 2.0;
+        let half_h = area.height as f32 / 2.0;
+        let nx = (x - area.x) as f32 + 0.5 - half_w;
+        let ny = (y - area.y) as f32 + 0.5 - half_h;
+        let r2 = ((nx / half_w).powi(2) + (ny / half_h).powi(2)) / 2.0;
+        VIGNETTE_STRENGTH * r2 * r2
+    }
+
+    /// Redraw fading ghosts into blank cells and remember what is lit now.
+    fn persist(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        if self.phosphor.len() != area.area() as usize {
+            self.phosphor = vec![DARK; area.area() as usize];
+        }
+        for (pos, glow) in area.positions().zip(self.phosphor.iter_mut()) {
+            let cell = &mut buf[pos];
+            if cell.symbol() != " " {
+                *glow = Phosphor {
+                    ch: cell.symbol().chars().next().unwrap_or(' '),
+                    fg: cell.fg,
+                    level: 1.0,
+                };
+            } else if glow.level > GHOST_MIN {
+                let base = if cell.bg == Color::Reset {
+                    ctx.theme.bg
+                } else {
+                    cell.bg
+                };
+                let fg = lerp_color(base, glow.fg, glow.level * GHOST_STRENGTH);
+                cell.set_char(glow.ch).set_fg(fg);
+            }
+        }
+    }
+
+    /// Tint the blank cells beside every digit stroke, red on the left and
+    /// blue on the right, and shift the stroke edges the same way.
+    fn fringe(buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        let clock = ctx.digits.area().intersection(area);
+        let lit = |x: u16, y: u16| ctx.digits.contains(x, y);
+        for y in clock.y..clock.y + clock.height {
+            for x in clock.x..clock.x + clock.width {
+                if !lit(x, y) {
+                    continue;
+                }
+                let left = (x > area.x).then(|| x - 1);
+                let right = (x + 1 < area.x + area.width).then_some(x + 1);
+                for (side, fringe) in [(left, FRINGE_LEFT), (right, FRINGE_RIGHT)] {
+                    let Some(side) = side.filter(|&side| !lit(side, y)) else {
+                        continue;
+                    };
+                    let stroke = &mut buf[(x, y)];
+                    stroke.fg = lerp_color(stroke.fg, fringe, EDGE_SHIFT);
+                    let neighbour = &mut buf[(side, y)];
+                    if !ctx.transparent && neighbour.symbol() == " " {
+                        neighbour.bg = lerp_color(neighbour.bg, fringe, FRINGE_STRENGTH);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Effect for Crt {
+    fn z_order(&self) -> i32 {
+        20
+    }
+
+    fn tick(&mut self, dt: f32) {
+        if self.flicker {
+            self.dip_timer -= dt;
+            if self.dip_timer <= 0.0 {
+                self.dip_timer = FLICKER_STEP;
+                // Mostly tiny wobbles, with the odd deeper dip
+                self.dip = self.rng.f32().powi(3) * FLICKER_DEPTH;
+            }
+        }
+        let fade = (-dt / PERSISTENCE_SECONDS).exp();
+        for glow in &mut self.phosphor {
+            glow.level *= fade;
+        }
+    }
+
+    fn resize(&mut self, _width: u16, _height: u16) {
+        self.phosphor.clear();
+    }
+
+    /// Ghosts first, so the other passes treat them like anything else on
+    /// screen, then the fringes, then vignette and flicker darken every
+    /// cell together. Like the scanlines, nothing is drawn at 16 colors or
+    /// in mono, where these shades cannot be shown.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        if matches!(ctx.color_mode, ColorMode::Ansi16 | ColorMode::Mono) {
+            return;
+        }
+        if self.persistence {
+            self.persist(buf, area, ctx);
+        }
+        if self.chromatic {
+            Self::fringe(buf, area, ctx);
+        }
+        if !self.vignette && !self.flicker {
+            return;
+        }
+        let dip = if self.flicker { self.dip } else { 0.0 };
+        for pos in area.positions() {
+            let vignette = if self.vignette {
+                Self::vignette_at(area, pos.x, pos.y)
+            } else {
+                0.0
+            };
+            let dark = 1.0 - (1.0 - vignette) * (1.0 - dip);
+            if dark < 0.005 {
+                continue;
+            }
+            let cell = &mut buf[pos];
+            cell.fg = lerp_color(cell.fg, BLACK, dark);
+            if !ctx.transparent {
+                cell.bg = lerp_color(cell.bg, BLACK, dark);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{ClearZone, DigitMask};
+    use crate::theme::{color_rgb, Theme};
+
+    const ALL: [CrtPass; 4] = [
+        CrtPass::Vignette,
+        CrtPass::Flicker,
+        CrtPass::Persistence,
+        CrtPass::Chromatic,
+    ];
+
+    fn ctx(theme: &Theme) -> RenderContext<'_> {
+        RenderContext {
+            theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::from_lines(Rect::new(8, 4, 3, 1), &["███".to_string()]),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        }
+    }
+
+    /// A theme-filled 20x10 screen with a digit stroke in the middle.
+    fn frame(theme: &Theme) -> Buffer {
+        let area = Rect::new(0, 0, 20, 10);
+        let mut buf = Buffer::empty(area);
+        for pos in area.positions() {
+            buf[pos].set_bg(theme.bg).set_fg(theme.accent);
+        }
+        for x in 8..11 {
+            buf[(x, 4)].set_char('█');
+        }
+        buf
+    }
+
+    fn draw(crt: &mut Crt, buf: &mut Buffer, ctx: &RenderContext) {
+        let area = buf.area;
+        crt.render(buf, area, ctx);
+    }
+
+    fn brightness(c: Color) -> u32 {
+        let (r, g, b) = color_rgb(c).unwrap();
+        r as u32 + g as u32 + b as u32
+    }
+
+    #[test]
+    fn test_vignette_darkens_corners() {
+        let theme = Theme::void();
+        let mut crt = Crt::new(&[CrtPass::Vignette], Some(1));
+        let mut buf = frame(&theme);
+        draw(&mut crt, &mut buf, &ctx(&theme));
+        assert!(brightness(buf[(0, 0)].fg) < brightness(buf[(10, 5)].fg));
+        assert!(brightness(buf[(0, 5)].fg) < brightness(buf[(5, 5)].fg));
+        assert_eq!(buf[(10, 5)].fg, theme.accent);
+    }
+
+    #[test]
+    fn test_flicker_deterministic_with_seed() {
+        let mut a = Crt::new(&[CrtPass::Flicker], Some(5));
+        let mut b = Crt::new(&[CrtPass::Flicker], Some(5));
+        let mut dips = Vec::new();
+        for _ in 0..40 {
+            a.tick(0.05);
+            b.tick(0.05);
+            assert_eq!(a.dip, b.dip);
+            dips.push(a.dip);
+        }
+        assert!(dips.iter().all(|d| (0.0..=FLICKER_DEPTH).contains(d)));
+        assert!(dips.iter().any(|&d| d != dips[0]));
+    }
+
+    #[test]
+    fn test_persistence_leaves_fading_ghost() {
+        let theme = Theme::void();
+        let mut crt = Crt::new(&[CrtPass::Persistence], None);
+        let mut buf = frame(&theme);
+        draw(&mut crt, &mut buf, &ctx(&theme));
+
+        // The stroke is gone in the next frame but still glows faintly
+        let blank = || {
+            let mut buf = frame(&theme);
+            for x in 8..11 {
+                buf[(x, 4)].set_char(' ');
+            }
+            buf
+        };
+        crt.tick(0.03);
+        let mut next = blank();
+        draw(&mut crt, &mut next, &ctx(&theme));
+        assert_eq!(next[(9, 4)].symbol(), "█");
+        let ghost = brightness(next[(9, 4)].fg);
+        assert!(ghost < brightness(theme.accent));
+
+        crt.tick(0.1);
+        let mut later = blank();
+        draw(&mut crt, &mut later, &ctx(&theme));
+        assert!(brightness(later[(9, 4)].fg) < ghost);
+
+        crt.tick(1.0);
+        let mut gone = blank();
+        draw(&mut crt, &mut gone, &ctx(&theme));
+        assert_eq!(gone[(9, 4)].symbol(), " ");
+    }
+
+    #[test]
+    fn test_chromatic_fringes() {
+        let theme = Theme::void();
+        let mut crt = Crt::new(&[CrtPass::Chromatic], None);
+        let mut buf = frame(&theme);
+        draw(&mut crt, &mut buf, &ctx(&theme));
+        let (lr, _, lb) = color_rgb(buf[(7, 4)].bg).unwrap();
+        let (rr, _, rb) = color_rgb(buf[(11, 4)].bg).unwrap();
+        assert!(lr > lb, "red fringe on the left");
+        assert!(rb > rr, "blue fringe on the right");
+        assert_eq!(buf[(7, 5)].bg, theme.bg);
+    }
+
+    #[test]
+    fn test_chromatic_skips_other_blocks() {
+        // A half-lit moon next to the clock is not a digit stroke
+        let theme = Theme::void();
+        let mut crt = Crt::new(&[CrtPass::Chromatic], None);
+        let mut buf = frame(&theme);
+        buf[(8, 4)].set_char('▀');
+        let untouched = buf.clone();
+        let ctx = RenderContext {
+            digits: DigitMask::from_lines(Rect::new(8, 4, 3, 1), &["   ".to_string()]),
+            ..ctx(&theme)
+        };
+        draw(&mut crt, &mut buf, &ctx);
+        assert_eq!(buf, untouched);
+    }
+
+    #[test]
+    fn test_passes_toggle_independently() {
+        let theme = Theme::void();
+        let untouched = frame(&theme);
+        let mut buf = frame(&theme);
+        draw(&mut Crt::new(&[], Some(1)), &mut buf, &ctx(&theme));
+        assert_eq!(buf, untouched);
+
+        let mut all = Crt::new(&ALL, Some(1));
+        all.tick(0.05);
+        let mut buf = frame(&theme);
+        let ctx16 = RenderContext {
+            color_mode: ColorMode::Ansi16,
+            ..ctx(&theme)
+        };
+        draw(&mut all, &mut buf, &ctx16);
+        assert_eq!(buf, untouched, "skipped at 16 colors");
+    }
+}
@@ -0,0 +1,223 @@
+//! Neon glow: a faint halo of the accent color around the lit digit cells.
+
+use std::f32::consts::TAU;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Color,
+};
+
+use super::{Effect, RenderContext};
+use crate::color::ColorMode;
+use crate::theme::lerp_color;
+
+/// Default halo radius in columns.
+pub const DEFAULT_RADIUS: u16 = 3;
+
+/// Default blend toward the accent right next to a digit.
+pub const DEFAULT_INTENSITY: f32 = 0.3;
+
+/// How far the halo dims at the low point of a pulse.
+const PULSE_DEPTH: f32 = 0.6;
+
+/// The glow halo, drawn over the clock digits and under the scanlines.
+#[derive(Debug, Clone, Copy)]
+pub struct Glow {
+    /// Reach of the halo in columns; rows count double
+    pub radius: u16,
+    /// Blend toward the accent next to a digit (0.0-1.0)
+    pub intensity: f32,
+    /// Breathe along with the blinking colons
+    pub pulse: bool,
+}
+
+impl Glow {
+    /// Halo strength multiplier at `elapsed` seconds. Colons blink on for
+    /// the first half of every second; the pulse peaks in the middle of it.
+    fn pulse_factor(&self, elapsed: f32) -> f32 {
+        if !self.pulse {
+            return 1.0;
+        }
+        let wave = 0.5 + 0.5 * ((elapsed - 0.25) * TAU).cos();
+        1.0 - PULSE_DEPTH * (1.0 - wave)
+    }
+}
+
+impl Effect for Glow {
+    fn z_order(&self) -> i32 {
+        5
+    }
+
+    fn tick(&mut self, _dt: f32) {}
+
+    fn resize(&mut self, _width: u16, _height: u16) {}
+
+    /// Blend the background of blank cells near the digits toward the
+    /// accent, fading with distance. Like the aurora, this needs a theme
+    /// background and a palette fine enough for a subtle tint.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        if ctx.transparent || matches!(ctx.color_mode, ColorMode::Ansi16 | ColorMode::Mono) {
+            return;
+        }
+        let clock = ctx.digits.area().intersection(area);
+        if clock.is_empty() || self.radius == 0 {
+            return;
+        }
+        let strength = self.intensity * self.pulse_factor(ctx.elapsed);
+        if strength < 0.01 {
+            return;
+        }
+
+        let lit_at = |x: i32, y: i32| {
+            let (Ok(x), Ok(y)) = (u16::try_from(x), u16::try_from(y)) else {
+                return false;
+            };
+            clock.contains(Position { x, y }) && ctx.digits.contains(x, y)
+        };
+
+        // Rows are about twice as tall as columns are wide
+        let (rx, ry) = (self.radius as i32, (self.radius as i32 + 1) / 2);
+        let reach = self.radius as f32 + 1.0;
+        let halo = Rect::new(
+            clock.x.saturating_sub(rx as u16),
+            clock.y.saturating_sub(ry as u16),
+            clock.width + 2 * rx as u16,
+            clock.height + 2 * ry as u16,
+        )
+        .intersection(area);
+
+        for pos in halo.positions() {
+            if buf[pos].symbol() != " " {
+                continue;
+            }
+            let (x, y) = (pos.x as i32, pos.y as i32);
+            let mut nearest = f32::INFINITY;
+            for dy in -ry..=ry {
+                for dx in -rx..=rx {
+                    if lit_at(x + dx, y + dy) {
+                        let d = ((dx * dx + 4 * dy * dy) as f32).sqrt();
+                        nearest = nearest.min(d);
+                    }
+                }
+            }
+            if nearest >= reach {
+                continue;
+            }
+            let falloff = (1.0 - nearest / reach).powi(2);
+            let cell = &mut buf[pos];
+            let base = if cell.bg == Color::Reset {
+                ctx.theme.bg
+            } else {
+                cell.bg
+            };
+            cell.bg = lerp_color(base, ctx.theme.accent, strength * falloff);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::effects::{ClearZone, DigitMask};
+    use crate::theme::Theme;
+
+    fn glow() -> Glow {
+        Glow {
+            radius: DEFAULT_RADIUS,
+            intensity: DEFAULT_INTENSITY,
+            pulse: false,
+        }
+    }
+
+    /// A 3x3 block of digit cells in the middle of a 20x10 screen.
+    fn render(mut glow: Glow, ctx: &RenderContext) -> Buffer {
+        let area = Rect::new(0, 0, 20, 10);
+        let mut buf = Buffer::empty(area);
+        for y in 4..7 {
+            for x in 8..11 {
+                buf[(x, y)].set_char('█');
+            }
+        }
+        glow.render(&mut buf, area, ctx);
+        buf
+    }
+
+    fn ctx(theme: &Theme) -> RenderContext<'_> {
+        RenderContext {
+            theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.25,
+            digits: DigitMask::from_lines(Rect::new(8, 4, 3, 3), &vec!["███".to_string(); 3]),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        }
+    }
+
+    /// How far a background has moved from the theme's toward the accent.
+    fn tint(theme: &Theme, bg: Color) -> u32 {
+        let (r, g, b) = crate::theme::color_rgb(bg).unwrap();
+        let (br, bg_, bb) = crate::theme::color_rgb(theme.bg).unwrap();
+        r.abs_diff(br) as u32 + g.abs_diff(bg_) as u32 + b.abs_diff(bb) as u32
+    }
+
+    #[test]
+    fn test_halo_fades_with_distance() {
+        let theme = Theme::void();
+        let buf = render(glow(), &ctx(&theme));
+        let near = tint(&theme, buf[(11, 5)].bg);
+        let far = tint(&theme, buf[(13, 5)].bg);
+        assert!(near > far && far > 0, "near {near}, far {far}");
+        assert_eq!(buf[(19, 5)].bg, Color::Reset, "outside the radius");
+        assert_eq!(buf[(9, 5)].bg, Color::Reset, "digit cells keep their bg");
+    }
+
+    #[test]
+    fn test_intensity_scales_halo() {
+        let theme = Theme::void();
+        let soft = render(glow(), &ctx(&theme));
+        let strong = render(
+            Glow {
+                intensity: 0.8,
+                ..glow()
+            },
+            &ctx(&theme),
+        );
+        assert!(tint(&theme, strong[(11, 5)].bg) > tint(&theme, soft[(11, 5)].bg));
+    }
+
+    #[test]
+    fn test_pulse_follows_blink() {
+        let g = Glow {
+            pulse: true,
+            ..glow()
+        };
+        assert_eq!(g.pulse_factor(0.25), 1.0);
+        assert!(g.pulse_factor(0.75) < 0.5);
+        assert_eq!(glow().pulse_factor(0.75), 1.0);
+    }
+
+    #[test]
+    fn test_only_digit_strokes_glow() {
+        // Block glyphs the font did not draw (a planet, say) get no halo
+        let theme = Theme::void();
+        let ctx = RenderContext {
+            digits: DigitMask::from_lines(Rect::new(8, 4, 3, 3), &vec!["   ".to_string(); 3]),
+            ..ctx(&theme)
+        };
+        let buf = render(glow(), &ctx);
+        assert!(buf.content().iter().all(|c| c.bg == Color::Reset));
+    }
+
+    #[test]
+    fn test_skipped_when_transparent() {
+        let theme = Theme::void();
+        let ctx = RenderContext {
+            transparent: true,
+            ..ctx(&theme)
+        };
+        let buf = render(glow(), &ctx);
+        assert!(buf.content().iter().all(|c| c.bg == Color::Reset));
+    }
+}
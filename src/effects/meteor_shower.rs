@@ -0,0 +1,152 @@
+//! Calendar of the major annual meteor showers.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+
+/// A major annual meteor shower.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum MeteorShower {
+    /// Early January, from Boötes
+    Quadrantids,
+    /// Late April, from Lyra
+    Lyrids,
+    /// Early May, from Aquarius
+    EtaAquariids,
+    /// Mid August, from Perseus
+    Perseids,
+    /// Late October, from Orion
+    Orionids,
+    /// Mid November, from Leo
+    Leonids,
+    /// Mid December, from Gemini
+    Geminids,
+    /// Late December, from Ursa Minor
+    Ursids,
+}
+
+/// When and where a shower peaks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShowerInfo {
+    /// Peak date (month, day)
+    pub peak: (u32, u32),
+    /// Days from the peak at which activity has fallen to about a third
+    pub width: f32,
+    /// Zenithal hourly rate at the peak
+    pub zhr: f32,
+    /// Radiant right ascension in hours
+    pub ra: f64,
+    /// Radiant declination in degrees
+    pub dec: f64,
+}
+
+/// Activity below this fraction of the peak is ignored.
+const MIN_ACTIVITY: f32 = 0.05;
+
+impl MeteorShower {
+    pub const ALL: [Self; 8] = [
+        Self::Quadrantids,
+        Self::Lyrids,
+        Self::EtaAquariids,
+        Self::Perseids,
+        Self::Orionids,
+        Self::Leonids,
+        Self::Geminids,
+        Self::Ursids,
+    ];
+
+    pub fn info(self) -> ShowerInfo {
+        let (peak, width, zhr, ra, dec) = match self {
+            Self::Quadrantids => ((1, 3), 0.6, 110.0, 15.3, 49.5),
+            Self::Lyrids => ((4, 22), 1.0, 18.0, 18.07, 34.0),
+            Self::EtaAquariids => ((5, 6), 3.0, 50.0, 22.5, -1.0),
+            Self::Perseids => ((8, 12), 2.5, 100.0, 3.2, 58.0),
+            Self::Orionids => ((10, 21), 2.5, 20.0, 6.4, 16.0),
+            Self::Leonids => ((11, 17), 1.0, 15.0, 10.2, 22.0),
+            Self::Geminids => ((12, 14), 1.0, 150.0, 7.5, 33.0),
+            Self::Ursids => ((12, 22), 0.5, 10.0, 14.5, 76.0),
+        };
+        ShowerInfo {
+            peak,
+            width,
+            zhr,
+            ra,
+            dec,
+        }
+    }
+
+    /// Activity at `t` as a fraction of the peak (0.0-1.0).
+    pub fn activity(self, t: DateTime<Utc>) -> f32 {
+        let info = self.info();
+        let (month, day) = info.peak;
+        // The nearest peak may fall in the previous or next year
+        let days = (t.year() - 1..=t.year() + 1)
+            .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+            .map(|peak| {
+                let peak = peak.and_hms_opt(0, 0, 0).unwrap().and_utc();
+                (t - peak).num_minutes() as f32 / 1440.0
+            })
+            .fold(f32::INFINITY, |nearest, d| {
+                if d.abs() < nearest.abs() {
+                    d
+                } else {
+                    nearest
+                }
+            });
+        (-(days / info.width).powi(2)).exp()
+    }
+
+    /// The most active shower at `t`, with its activity.
+    pub fn current(t: DateTime<Utc>) -> Option<(Self, f32)> {
+        Self::ALL
+            .into_iter()
+            .map(|shower| (shower, shower.activity(t)))
+            .filter(|&(_, activity)| activity >= MIN_ACTIVITY)
+            .max_by(|a, b| (a.0.info().zhr * a.1).total_cmp(&(b.0.info().zhr * b.1)))
+    }
+
+    /// How many times more often shooting stars appear during this shower
+    /// at `activity`, compared to a quiet night.
+    pub fn rate(self, activity: f32) -> f32 {
+        1.0 + self.info().zhr / 10.0 * activity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(y: i32, m: u32, d: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_peak_dates() {
+        assert_eq!(
+            MeteorShower::current(at(2024, 8, 12)),
+            Some((MeteorShower::Perseids, 1.0))
+        );
+        let (shower, activity) = MeteorShower::current(at(2024, 12, 13)).unwrap();
+        assert_eq!(shower, MeteorShower::Geminids);
+        assert!(activity > 0.3);
+        assert_eq!(MeteorShower::current(at(2024, 3, 1)), None);
+    }
+
+    #[test]
+    fn test_activity_wraps_around_new_year() {
+        assert!(MeteorShower::Quadrantids.activity(at(2025, 1, 3)) > 0.9);
+        let late = Utc.with_ymd_and_hms(2024, 12, 31, 12, 0, 0).unwrap();
+        let early = Utc.with_ymd_and_hms(2025, 1, 2, 12, 0, 0).unwrap();
+        assert!(
+            MeteorShower::Quadrantids.activity(late) < MeteorShower::Quadrantids.activity(early)
+        );
+        assert!(MeteorShower::Quadrantids.activity(late) > 0.0);
+    }
+
+    #[test]
+    fn test_rate_grows_with_activity() {
+        let perseids = MeteorShower::Perseids;
+        assert_eq!(perseids.rate(0.0), 1.0);
+        assert!(perseids.rate(1.0) > perseids.rate(0.5));
+        assert!(MeteorShower::Geminids.rate(1.0) > MeteorShower::Ursids.rate(1.0));
+    }
+}
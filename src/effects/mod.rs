@@ -1,3 +1,374 @@
-pub mod starfield;
-pub mod shooting_star;
+//! Background and overlay effects drawn around the clock.
+//!
+//! Every effect implements [`Effect`] and is listed in [`EffectKind`], the
+//! registry `--effects` selects from. Adding an effect means adding a
+//! variant and a constructor arm in [`EffectKind::create`].
+
+pub mod aurora;
+pub mod catalog;
+pub mod constellation;
+pub mod crt;
+pub mod glow;
+pub mod meteor_shower;
+mod noise;
+pub mod particles;
+pub mod planets;
 pub mod scanline;
+pub mod shooting_star;
+pub mod starfield;
+pub mod weather;
+
+use chrono::Utc;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+};
+
+use crate::astro::Location;
+use crate::color::ColorMode;
+use crate::theme::Theme;
+
+use self::aurora::{Aurora, AuroraStyle};
+use self::constellation::Constellations;
+use self::crt::{Crt, CrtPass};
+use self::glow::Glow;
+use self::meteor_shower::MeteorShower;
+use self::planets::Planets;
+use self::scanline::Scanlines;
+use self::shooting_star::ShootingStarManager;
+use self::starfield::{StarDirection, StarMode, Starfield};
+use self::weather::{Weather, WeatherKind};
+
+/// Everything an effect may read while drawing a frame.
+pub struct RenderContext<'a> {
+    pub theme: &'a Theme,
+    pub transparent: bool,
+    pub color_mode: ColorMode,
+    /// Seconds since start
+    pub elapsed: f32,
+    /// The cells the clock digits are drawn into
+    pub digits: DigitMask,
+    /// Area around the clock and date kept clear of stars
+    pub clear_zone: Rect,
+    pub clear_mode: ClearZone,
+}
+
+impl RenderContext<'_> {
+    /// Brightness multiplier (0.0-1.0) for a star drawn at `(x, y)`: lower
+    /// inside the clear zone, so stars do not clutter the gaps between the
+    /// digit strokes.
+    pub fn star_visibility(&self, x: u16, y: u16) -> f32 {
+        if !self.clear_zone.contains(Position { x, y }) {
+            return 1.0;
+        }
+        match self.clear_mode {
+            ClearZone::Dim => CLEAR_ZONE_DIM,
+            ClearZone::Hide => 0.0,
+            ClearZone::Off => 1.0,
+        }
+    }
+}
+
+/// The cells the clock font fills with digit strokes, laid out before the
+/// digits are drawn. Effects use it to find the strokes, so that other
+/// block glyphs in the buffer (a half-lit moon, say) are not taken for
+/// digits.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DigitMask {
+    area: Rect,
+    cells: Vec<bool>,
+}
+
+impl DigitMask {
+    /// The non-blank cells of `lines` drawn from the top-left of `area`.
+    pub fn from_lines(area: Rect, lines: &[String]) -> Self {
+        let mut cells = vec![false; area.area() as usize];
+        for (row, line) in lines.iter().take(area.height as usize).enumerate() {
+            for (col, ch) in line.chars().take(area.width as usize).enumerate() {
+                cells[row * area.width as usize + col] = ch != ' ';
+            }
+        }
+        Self { area, cells }
+    }
+
+    /// The clock's bounding box.
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    /// Whether a digit stroke is drawn at `(x, y)`.
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        if !self.area.contains(Position { x, y }) {
+            return false;
+        }
+        let (col, row) = (x - self.area.x, y - self.area.y);
+        self.cells[row as usize * self.area.width as usize + col as usize]
+    }
+}
+
+/// How stars behave around the clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ClearZone {
+    /// Draw stars near the clock faintly
+    Dim,
+    /// Draw no stars near the clock
+    Hide,
+    /// Draw stars everywhere
+    Off,
+}
+
+/// Default margin around the clock, in columns.
+pub const DEFAULT_CLEAR_MARGIN: u16 = 2;
+
+/// Star brightness left inside a dimmed clear zone.
+const CLEAR_ZONE_DIM: f32 = 0.3;
+
+/// An animated layer drawn behind or in front of the clock.
+pub trait Effect {
+    /// Drawing order. Negative values are drawn behind the clock digits and
+    /// positive values over them; lower values are drawn first.
+    fn z_order(&self) -> i32;
+
+    /// Advance the animation by `dt` seconds.
+    fn tick(&mut self, dt: f32);
+
+    /// The terminal was resized.
+    fn resize(&mut self, width: u16, height: u16);
+
+    /// Draw into `buf` within `area`. Most effects only draw here; ones that
+    /// react to what is already on screen (the CRT phosphor, weather settling
+    /// on the digits) may also take note of it for the next frame.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext);
+
+    /// React to a user action. Effects ignore events they have no use for.
+    fn handle(&mut self, _event: EffectEvent) {}
+}
+
+/// User actions forwarded to every effect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EffectEvent {
+    /// Jump to hyperspace for a few seconds
+    Warp,
+}
+
+/// Settings shared by the effect constructors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectSettings {
+    /// Star density (0-100)
+    pub stars: u32,
+    pub seed: Option<u64>,
+    /// Star drift speeds per layer (far, mid, near), cells/sec
+    pub parallax: [f32; 3],
+    /// Star twinkle amplitude (0.0-1.0)
+    pub twinkle: f32,
+    pub star_mode: StarMode,
+    pub star_direction: StarDirection,
+    /// Multiplier on the star drift speeds
+    pub star_speed: f32,
+    /// Seconds between shooting stars (min, max)
+    pub shooting_star_interval: (f32, f32),
+    /// Shooting star speed range, cells/sec
+    pub shooting_star_speed: (f32, f32),
+    /// Shooting star direction range, degrees counter-clockwise from right
+    pub shooting_star_angle: (f32, f32),
+    /// Shooting star path curvature (0.0-1.0)
+    pub shooting_star_curve: f32,
+    /// Shower to run regardless of the date
+    pub meteor_shower: Option<MeteorShower>,
+    /// Peak scanline opacity (0.0-1.0)
+    pub scanline_opacity: f32,
+    /// Scanline breathing speed
+    pub scanline_speed: f32,
+    /// Glow halo reach in columns
+    pub glow_radius: u16,
+    /// Glow blend toward the accent next to the digits (0.0-1.0)
+    pub glow_intensity: f32,
+    /// Pulse the glow with the blinking colons
+    pub glow_pulse: bool,
+    /// CRT passes to run
+    pub crt: Vec<CrtPass>,
+    /// Observer for the constellation sky, if known
+    pub location: Option<Location>,
+}
+
+/// Registry of available effects, by the name used with `--effects`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum EffectKind {
+    /// Curtains of colored light across the upper sky
+    Aurora,
+    /// Slowly drifting clouds of colored gas
+    Nebula,
+    /// A cratered moon in its current phase and a couple of planets
+    Planets,
+    /// Real constellation figures, turning with the sky at `--lat`/`--lon`
+    Constellations,
+    /// Drifting parallax stars
+    Starfield,
+    /// Occasional streaks across the sky
+    ShootingStars,
+    /// Neon halo of the accent color around the digits
+    Glow,
+    /// Snowflakes drifting down and settling on the digits
+    Snow,
+    /// Slanting rain splashing on the digits
+    Rain,
+    /// Faint cosmic dust drifting across the sky
+    Dust,
+    /// Faint CRT-style row tint over everything
+    Scanlines,
+    /// CRT tube finish: vignette, flicker, phosphor ghosts, color fringes
+    Crt,
+}
+
+/// Effects enabled when `--effects` is not given.
+pub const DEFAULT_EFFECTS: &str = "starfield,shooting-stars,scanlines";
+
+impl EffectKind {
+    pub fn create(self, width: u16, height: u16, settings: &EffectSettings) -> Box<dyn Effect> {
+        match self {
+            Self::Aurora => Box::new(Aurora::new(AuroraStyle::Bands, settings.seed)),
+            Self::Nebula => Box::new(Aurora::new(AuroraStyle::Clouds, settings.seed)),
+            Self::Planets => Box::new(Planets::new(width, height, settings.seed)),
+            Self::Constellations => Box::new(Constellations::new(width, height, settings.location)),
+            Self::Starfield => Box::new(
+                Starfield::new(width, height, settings.stars, settings.seed)
+                    .with_parallax(settings.parallax)
+                    .with_twinkle(settings.twinkle)
+                    .with_mode(settings.star_mode)
+                    .with_drift(settings.star_direction, settings.star_speed),
+            ),
+            Self::ShootingStars => Box::new(
+                ShootingStarManager::new(width, height, settings.seed, Utc::now())
+                    .with_interval(settings.shooting_star_interval)
+                    .with_speed(settings.shooting_star_speed)
+                    .with_angle(settings.shooting_star_angle)
+                    .with_curve(settings.shooting_star_curve)
+                    .with_location(settings.location)
+                    .with_shower(settings.meteor_shower),
+            ),
+            Self::Glow => Box::new(Glow {
+                radius: settings.glow_radius,
+                intensity: settings.glow_intensity,
+                pulse: settings.glow_pulse,
+            }),
+            Self::Snow => Box::new(Weather::new(
+                WeatherKind::Snow,
+                width,
+                height,
+                settings.seed,
+            )),
+            Self::Rain => Box::new(Weather::new(
+                WeatherKind::Rain,
+                width,
+                height,
+                settings.seed,
+            )),
+            Self::Dust => Box::new(Weather::new(
+                WeatherKind::Dust,
+                width,
+                height,
+                settings.seed,
+            )),
+            Self::Scanlines => Box::new(Scanlines {
+                opacity: settings.scanline_opacity,
+                speed: settings.scanline_speed,
+            }),
+            Self::Crt => Box::new(Crt::new(&settings.crt, settings.seed)),
+        }
+    }
+}
+
+/// Instantiate `kinds` (duplicates dropped) sorted by z-order. Effects with
+/// the same z-order keep the order they were listed in.
+pub fn build(
+    kinds: &[EffectKind],
+    width: u16,
+    height: u16,
+    settings: &EffectSettings,
+) -> Vec<Box<dyn Effect>> {
+    let mut seen = Vec::new();
+    let mut effects: Vec<Box<dyn Effect>> = kinds
+        .iter()
+        .filter(|kind| {
+            let first = !seen.contains(*kind);
+            seen.push(**kind);
+            first
+        })
+        .map(|kind| kind.create(width, height, settings))
+        .collect();
+    effects.sort_by_key(|effect| effect.z_order());
+    effects
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> EffectSettings {
+        EffectSettings {
+            stars: 40,
+            seed: Some(1),
+            parallax: starfield::DEFAULT_PARALLAX,
+            twinkle: starfield::DEFAULT_TWINKLE,
+            star_mode: StarMode::Drift,
+            star_direction: starfield::DEFAULT_DIRECTION,
+            star_speed: 1.0,
+            shooting_star_interval: shooting_star::DEFAULT_INTERVAL,
+            shooting_star_speed: shooting_star::DEFAULT_SPEED,
+            shooting_star_angle: shooting_star::DEFAULT_ANGLE,
+            shooting_star_curve: shooting_star::DEFAULT_CURVE,
+            meteor_shower: None,
+            scanline_opacity: scanline::DEFAULT_OPACITY,
+            scanline_speed: scanline::DEFAULT_SPEED,
+            glow_radius: glow::DEFAULT_RADIUS,
+            glow_intensity: glow::DEFAULT_INTENSITY,
+            glow_pulse: false,
+            crt: vec![CrtPass::Vignette],
+            location: None,
+        }
+    }
+
+    #[test]
+    fn test_build_sorts_by_z_order() {
+        let kinds = [
+            EffectKind::Scanlines,
+            EffectKind::ShootingStars,
+            EffectKind::Starfield,
+        ];
+        let effects = build(&kinds, 80, 24, &settings());
+        let z: Vec<i32> = effects.iter().map(|e| e.z_order()).collect();
+        let mut sorted = z.clone();
+        sorted.sort();
+        assert_eq!(z, sorted);
+        assert!(z[0] < 0, "stars belong behind the clock");
+        assert!(*z.last().unwrap() > 0, "scanlines belong over the clock");
+    }
+
+    #[test]
+    fn test_star_visibility_in_clear_zone() {
+        let theme = Theme::void();
+        let mut ctx = RenderContext {
+            theme: &theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::default(),
+            clear_zone: Rect::new(8, 4, 24, 7),
+            clear_mode: ClearZone::Dim,
+        };
+        assert_eq!(ctx.star_visibility(0, 0), 1.0);
+        assert_eq!(ctx.star_visibility(8, 4), CLEAR_ZONE_DIM);
+        assert_eq!(ctx.star_visibility(32, 4), 1.0);
+        ctx.clear_mode = ClearZone::Hide;
+        assert_eq!(ctx.star_visibility(20, 7), 0.0);
+        ctx.clear_mode = ClearZone::Off;
+        assert_eq!(ctx.star_visibility(20, 7), 1.0);
+    }
+
+    #[test]
+    fn test_build_drops_duplicates() {
+        let kinds = [EffectKind::Starfield, EffectKind::Starfield];
+        assert_eq!(build(&kinds, 80, 24, &settings()).len(), 1);
+        assert!(build(&[], 80, 24, &settings()).is_empty());
+    }
+}
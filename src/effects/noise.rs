@@ -0,0 +1,96 @@
+//! Smooth 2D value noise for cloud and band effects.
+
+/// Seeded value noise: random values on an integer lattice, smoothly
+/// interpolated in between.
+#[derive(Debug, Clone)]
+pub struct ValueNoise {
+    seed: u32,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed: (seed ^ (seed >> 32)) as u32,
+        }
+    }
+
+    /// Pseudo-random value in 0.0-1.0 for a lattice point.
+    fn lattice(&self, x: i32, y: i32) -> f32 {
+        let mut h = (x as u32)
+            .wrapping_mul(0x27d4_eb2d)
+            .wrapping_add((y as u32).wrapping_mul(0x1656_67b1))
+            .wrapping_add(self.seed.wrapping_mul(0x9e37_79b9));
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^= h >> 16;
+        h as f32 / u32::MAX as f32
+    }
+
+    /// Noise at `(x, y)`, in 0.0-1.0. One lattice cell per unit.
+    pub fn sample(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (ix, iy) = (x0 as i32, y0 as i32);
+        let fade = |t: f32| t * t * (3.0 - 2.0 * t);
+        let (u, v) = (fade(x - x0), fade(y - y0));
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+        let top = lerp(self.lattice(ix, iy), self.lattice(ix + 1, iy), u);
+        let bottom = lerp(self.lattice(ix, iy + 1), self.lattice(ix + 1, iy + 1), u);
+        lerp(top, bottom, v)
+    }
+
+    /// Fractal noise: `octaves` layers, each twice the frequency and half
+    /// the weight of the last. Normalized to 0.0-1.0.
+    pub fn fbm(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let (mut sum, mut weight, mut total, mut freq) = (0.0, 1.0, 0.0, 1.0);
+        for octave in 0..octaves {
+            // Offset each octave so their lattices don't line up
+            let offset = octave as f32 * 17.31;
+            sum += self.sample(x * freq + offset, y * freq - offset) * weight;
+            total += weight;
+            weight *= 0.5;
+            freq *= 2.0;
+        }
+        if total > 0.0 {
+            sum / total
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_noise_range_and_determinism() {
+        let a = ValueNoise::new(42);
+        let b = ValueNoise::new(42);
+        for i in 0..500 {
+            let (x, y) = (i as f32 * 0.37 - 50.0, i as f32 * 0.11 - 20.0);
+            let n = a.fbm(x, y, 4);
+            assert!((0.0..=1.0).contains(&n), "{n} out of range");
+            assert_eq!(n, b.fbm(x, y, 4));
+        }
+    }
+
+    #[test]
+    fn test_noise_is_continuous() {
+        let n = ValueNoise::new(7);
+        for i in 0..200 {
+            let x = i as f32 * 0.05;
+            assert!((n.sample(x, 1.3) - n.sample(x + 0.01, 1.3)).abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        let a = ValueNoise::new(1);
+        let b = ValueNoise::new(2);
+        let differs =
+            (0..20).any(|i| a.sample(i as f32 + 0.5, 0.5) != b.sample(i as f32 + 0.5, 0.5));
+        assert!(differs);
+    }
+}
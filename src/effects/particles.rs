@@ -0,0 +1,334 @@
+//! A small particle system shared by the weather effects: particles spawn,
+//! move, age and fade out, and come to rest on top of the clock digits.
+
+use fastrand::Rng;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::DigitMask;
+use crate::theme::lerp_color;
+
+/// Sideways sway frequency, radians per second.
+const SWAY_FREQUENCY: f32 = 1.3;
+
+/// How far past the left and right edges particles may wander before they
+/// are dropped, in cells.
+const MARGIN: f32 = 4.0;
+
+/// A single particle.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Particle {
+    /// Position in cells
+    pub x: f32,
+    pub y: f32,
+    /// Velocity in cells/sec
+    pub vx: f32,
+    pub vy: f32,
+    /// Seconds since it spawned
+    pub age: f32,
+    /// Seconds it lives
+    pub life: f32,
+    pub glyph: char,
+    /// Peak brightness (0.0-1.0)
+    pub brightness: f32,
+    /// Seconds to fade in after spawning, and out before dying
+    pub fade: (f32, f32),
+    /// Sideways sway amplitude in cells/sec
+    pub sway: f32,
+    /// Sway phase offset
+    pub phase: f32,
+    /// Glyph shown while resting on a digit
+    landed: Option<char>,
+}
+
+impl Particle {
+    pub fn new(x: f32, y: f32, vx: f32, vy: f32, life: f32, glyph: char) -> Self {
+        Self {
+            x,
+            y,
+            vx,
+            vy,
+            age: 0.0,
+            life,
+            glyph,
+            brightness: 1.0,
+            fade: (0.0, 0.0),
+            sway: 0.0,
+            phase: 0.0,
+            landed: None,
+        }
+    }
+
+    pub fn with_brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    pub fn with_fade(mut self, fade_in: f32, fade_out: f32) -> Self {
+        self.fade = (fade_in, fade_out);
+        self
+    }
+
+    pub fn with_sway(mut self, amount: f32, phase: f32) -> Self {
+        self.sway = amount;
+        self.phase = phase;
+        self
+    }
+
+    /// Current brightness, faded in and out over its life.
+    pub fn opacity(&self) -> f32 {
+        let (fade_in, fade_out) = self.fade;
+        let rise = if fade_in > 0.0 {
+            self.age / fade_in
+        } else {
+            1.0
+        };
+        let fall = if fade_out > 0.0 {
+            (self.life - self.age) / fade_out
+        } else {
+            1.0
+        };
+        self.brightness * rise.min(fall).clamp(0.0, 1.0)
+    }
+}
+
+/// What particles turn into when they come to rest on a digit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Landing {
+    pub glyph: char,
+    /// Seconds a landed particle lasts (min, max)
+    pub seconds: (f32, f32),
+}
+
+/// Digit cells particles cannot pass through, taken from the last drawn
+/// frame. Coordinates are relative to the drawing area.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Obstacles {
+    width: u16,
+    height: u16,
+    cells: Vec<bool>,
+}
+
+impl Obstacles {
+    /// The digit strokes of `digits` that fall within `area`.
+    pub fn from_digits(digits: &DigitMask, area: Rect) -> Self {
+        let mut cells = vec![false; area.area() as usize];
+        for pos in digits.area().intersection(area).positions() {
+            if digits.contains(pos.x, pos.y) {
+                let (x, y) = (pos.x - area.x, pos.y - area.y);
+                cells[y as usize * area.width as usize + x as usize] = true;
+            }
+        }
+        Self {
+            width: area.width,
+            height: area.height,
+            cells,
+        }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0
+            && y >= 0
+            && x < self.width as i32
+            && y < self.height as i32
+            && self.cells[y as usize * self.width as usize + x as usize]
+    }
+}
+
+/// A set of particles sharing one landing behavior.
+#[derive(Debug, Clone)]
+pub struct ParticleSystem {
+    pub particles: Vec<Particle>,
+    landing: Landing,
+}
+
+impl ParticleSystem {
+    pub fn new(landing: Landing) -> Self {
+        Self {
+            particles: Vec::new(),
+            landing,
+        }
+    }
+
+    pub fn spawn(&mut self, particle: Particle) {
+        self.particles.push(particle);
+    }
+
+    /// Move and age every particle by `dt` seconds within a `width` x
+    /// `height` area. A particle that runs into a digit from above comes to
+    /// rest on top of it; one that hits the side of a stroke slides down it.
+    /// Particles leaving the bottom or sides, or buried by a digit that
+    /// appeared over them, are removed; ones still heading onto the screen
+    /// from beside it are kept.
+    pub fn update(
+        &mut self,
+        dt: f32,
+        width: u16,
+        height: u16,
+        obstacles: &Obstacles,
+        rng: &mut Rng,
+    ) {
+        let landing = self.landing;
+        let solid = |x: f32, y: f32| obstacles.contains(x.floor() as i32, y.floor() as i32);
+        for p in &mut self.particles {
+            p.age += dt;
+            if solid(p.x, p.y) {
+                p.age = p.life;
+                continue;
+            }
+            if p.landed.is_some() {
+                if solid(p.x, p.y + 1.0) {
+                    continue;
+                }
+                // The digit under it changed: fall again
+                p.landed = None;
+            }
+
+            let vx = p.vx + p.sway * (p.age * SWAY_FREQUENCY + p.phase).sin();
+            let (dx, dy) = (vx * dt, p.vy * dt);
+            // Step a cell at a time so fast particles cannot skip a stroke
+            let steps = dx.abs().max(dy.abs()).ceil().max(1.0) as usize;
+            let (x0, y0) = (p.x, p.y);
+            let mut hit = false;
+            for i in 1..=steps {
+                let t = i as f32 / steps as f32;
+                let (x, y) = (x0 + dx * t, y0 + dy * t);
+                if solid(x, y) {
+                    hit = true;
+                    break;
+                }
+                (p.x, p.y) = (x, y);
+            }
+            if !hit {
+                continue;
+            }
+            if solid(p.x, p.y + 1.0) {
+                let (min, max) = landing.seconds;
+                p.landed = Some(landing.glyph);
+                p.life = p.life.min(p.age + min + rng.f32() * (max - min));
+            } else {
+                p.vx = 0.0;
+                p.sway = 0.0;
+            }
+        }
+        // Off to one side only counts when heading further away
+        let (w, h) = (width as f32, height as f32);
+        self.particles.retain(|p| {
+            p.age < p.life
+                && p.y < h
+                && (p.x > -MARGIN || p.vx > 0.0)
+                && (p.x < w + MARGIN || p.vx < 0.0)
+        });
+    }
+
+    /// Draw every particle in `color`, faded toward `bg`, into blank cells
+    /// of `area`: the digits, date and anything else already drawn stay in
+    /// front.
+    pub fn render(&self, buf: &mut Buffer, area: Rect, bg: Color, color: Color) {
+        for p in &self.particles {
+            if p.x < 0.0 || p.y < 0.0 || p.x >= area.width as f32 || p.y >= area.height as f32 {
+                continue;
+            }
+            let opacity = p.opacity();
+            if opacity < 0.05 {
+                continue;
+            }
+            let pos = (area.x + p.x as u16, area.y + p.y as u16);
+            if let Some(cell) = buf.cell_mut(pos) {
+                if cell.symbol() == " " {
+                    let glyph = p.landed.unwrap_or(p.glyph);
+                    cell.set_char(glyph).set_fg(lerp_color(bg, color, opacity));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LANDING: Landing = Landing {
+        glyph: '▁',
+        seconds: (5.0, 5.0),
+    };
+
+    /// A 20x10 area with a digit stroke along row 6, columns 5-14.
+    fn obstacles() -> Obstacles {
+        let digits = DigitMask::from_lines(Rect::new(5, 6, 10, 1), &["█".repeat(10)]);
+        Obstacles::from_digits(&digits, Rect::new(0, 0, 20, 10))
+    }
+
+    fn run(system: &mut ParticleSystem, seconds: f32, obstacles: &Obstacles) {
+        let mut rng = Rng::with_seed(1);
+        for _ in 0..(seconds * 30.0) as usize {
+            system.update(1.0 / 30.0, 20, 10, obstacles, &mut rng);
+        }
+    }
+
+    #[test]
+    fn test_particles_move_and_expire() {
+        let mut system = ParticleSystem::new(LANDING);
+        system.spawn(Particle::new(1.0, 0.0, 0.0, 2.0, 1.0, '*'));
+        run(&mut system, 0.5, &Obstacles::default());
+        assert!((system.particles[0].y - 1.0).abs() < 0.1);
+        run(&mut system, 1.0, &Obstacles::default());
+        assert!(system.particles.is_empty());
+    }
+
+    #[test]
+    fn test_fast_particles_land_on_digits() {
+        let obstacles = obstacles();
+        let mut system = ParticleSystem::new(LANDING);
+        // Fast enough to cross several rows per frame
+        system.spawn(Particle::new(8.5, 0.0, 0.0, 120.0, 60.0, '*'));
+        system.spawn(Particle::new(1.5, 0.0, 0.0, 120.0, 60.0, '*'));
+        run(&mut system, 1.0, &obstacles);
+        assert_eq!(system.particles.len(), 1, "the other fell off the bottom");
+        let p = &system.particles[0];
+        assert!(p.landed.is_some());
+        assert_eq!((p.x as i32, p.y as i32), (8, 5));
+        // Landed particles melt away in time
+        run(&mut system, 6.0, &obstacles);
+        assert!(system.particles.is_empty());
+    }
+
+    #[test]
+    fn test_side_hit_slides_down() {
+        let digits = DigitMask::from_lines(Rect::new(10, 2, 1, 8), &vec!["█".to_string(); 8]);
+        let wall = Obstacles::from_digits(&digits, Rect::new(0, 0, 20, 10));
+        let mut system = ParticleSystem::new(LANDING);
+        system.spawn(Particle::new(8.5, 3.5, 10.0, 1.0, 60.0, '*'));
+        run(&mut system, 0.5, &wall);
+        let p = &system.particles[0];
+        assert_eq!(p.x as i32, 9);
+        assert_eq!(p.vx, 0.0);
+        assert!(p.landed.is_none());
+    }
+
+    #[test]
+    fn test_opacity_fades_in_and_out() {
+        let mut p = Particle::new(0.0, 0.0, 0.0, 0.0, 4.0, '·')
+            .with_brightness(0.8)
+            .with_fade(1.0, 1.0);
+        assert_eq!(p.opacity(), 0.0);
+        p.age = 2.0;
+        assert_eq!(p.opacity(), 0.8);
+        p.age = 3.5;
+        assert!((p.opacity() - 0.4).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_render_keeps_digits_in_front() {
+        let area = Rect::new(0, 0, 20, 10);
+        let mut buf = Buffer::empty(area);
+        buf[(3, 3)].set_char('█');
+        let mut system = ParticleSystem::new(LANDING);
+        system.spawn(Particle::new(3.2, 3.7, 0.0, 0.0, 9.0, '*'));
+        system.spawn(Particle::new(4.2, 3.7, 0.0, 0.0, 9.0, '*'));
+        let (black, white) = (Color::Rgb(0, 0, 0), Color::Rgb(255, 255, 255));
+        system.render(&mut buf, area, black, white);
+        assert_eq!(buf[(3, 3)].symbol(), "█");
+        assert_eq!(buf[(4, 3)].symbol(), "*");
+        assert_eq!(buf[(4, 3)].fg, white);
+    }
+}
@@ -0,0 +1,375 @@
+//! Planet and moon sprites drifting and orbiting behind the clock.
+//!
+//! Bodies are drawn with half blocks: every cell holds two square pixels,
+//! the upper one in the foreground color of `▀` and the lower one in its
+//! background. The moon is lit to match the real lunar phase.
+
+use std::f32::consts::TAU;
+
+use chrono::Utc;
+use fastrand::Rng;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::{Effect, RenderContext};
+use crate::astro;
+use crate::theme::{lerp_color, Theme};
+
+/// How strongly bodies stand out from the background (0.0-1.0).
+const BODY_STRENGTH: f32 = 0.8;
+
+/// Faint glow on the moon's unlit side, so a new moon is still a disc.
+const EARTHSHINE: f32 = 0.06;
+
+/// Seconds for one trip of the moon around the clock.
+const ORBIT_SECONDS: f32 = 240.0;
+
+/// Drift speed range of the planets, in pixels (columns) per second.
+const DRIFT_SPEED: (f32, f32) = (0.3, 0.8);
+
+/// How often the lunar phase is recomputed, in seconds.
+const PHASE_REFRESH_SECONDS: f32 = 60.0;
+
+/// Craters on the moon: center and radius in units of the moon's radius.
+const CRATERS: [(f32, f32, f32); 5] = [
+    (-0.35, -0.2, 0.2),
+    (0.3, 0.35, 0.15),
+    (0.1, -0.5, 0.12),
+    (0.5, -0.1, 0.1),
+    (-0.2, 0.55, 0.12),
+];
+
+/// The kinds of body in the sky.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BodyKind {
+    /// Cratered, lit by the current lunar phase
+    Moon,
+    /// Gas giant with a tilted ring
+    Ringed,
+    /// Gas giant with cloud bands
+    Banded,
+}
+
+/// How a body moves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Motion {
+    /// Ellipse around the screen center, starting at `angle` radians
+    Orbit { angle: f32 },
+    /// Sideways at `speed` pixels/sec at a fixed height (fraction of the
+    /// screen), wrapping around; `offset` is the starting column
+    Drift {
+        speed: f32,
+        height: f32,
+        offset: f32,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Body {
+    kind: BodyKind,
+    /// Radius relative to the moon's
+    radius: f32,
+    motion: Motion,
+}
+
+/// The planets-and-moon effect.
+#[derive(Debug, Clone)]
+pub struct Planets {
+    bodies: Vec<Body>,
+    /// Lunar phase, 0.0-1.0 (0 = new, 0.5 = full)
+    phase: f32,
+    phase_refresh: f32,
+    /// Seconds since start
+    time: f32,
+    width: u16,
+    height: u16,
+}
+
+impl Planets {
+    pub fn new(width: u16, height: u16, seed: Option<u64>) -> Self {
+        let mut rng = match seed {
+            Some(s) => Rng::with_seed(s.wrapping_add(0x9A4E7)),
+            None => Rng::new(),
+        };
+        // Each planet keeps to its own band of the sky: (top, extent)
+        let drift = |rng: &mut Rng, (top, extent): (f32, f32)| Motion::Drift {
+            speed: (DRIFT_SPEED.0 + rng.f32() * (DRIFT_SPEED.1 - DRIFT_SPEED.0))
+                * if rng.bool() { 1.0 } else { -1.0 },
+            height: top + rng.f32() * extent,
+            offset: rng.f32() * width as f32,
+        };
+        // Drawn in order: the planets far away, the moon in front
+        let bodies = vec![
+            Body {
+                kind: BodyKind::Banded,
+                radius: 1.0,
+                motion: drift(&mut rng, (0.15, 0.15)),
+            },
+            Body {
+                kind: BodyKind::Ringed,
+                radius: 0.6,
+                motion: drift(&mut rng, (0.7, 0.15)),
+            },
+            Body {
+                kind: BodyKind::Moon,
+                radius: 1.0,
+                motion: Motion::Orbit {
+                    angle: rng.f32() * TAU,
+                },
+            },
+        ];
+        Self {
+            bodies,
+            phase: astro::moon_phase(Utc::now()) as f32,
+            phase_refresh: PHASE_REFRESH_SECONDS,
+            time: 0.0,
+            width,
+            height,
+        }
+    }
+
+    /// Scale the bodies to the screen: the moon is about a fifth of the
+    /// screen tall, within reason.
+    fn scale(height: u16) -> f32 {
+        (height as f32 * 2.0 / 10.0).clamp(3.0, 10.0)
+    }
+
+    /// Center of `body` in pixels.
+    fn position(&self, body: &Body) -> (f32, f32) {
+        let (w, h) = (self.width as f32, self.height as f32 * 2.0);
+        match body.motion {
+            Motion::Orbit { angle } => {
+                let a = angle + self.time / ORBIT_SECONDS * TAU;
+                (w / 2.0 + a.cos() * w * 0.38, h / 2.0 + a.sin() * h * 0.32)
+            }
+            Motion::Drift {
+                speed,
+                height,
+                offset,
+            } => {
+                // Leave the screen fully before wrapping around
+                let margin = body.radius * Self::scale(self.height) * 2.5;
+                let span = w + 2.0 * margin;
+                let x = (offset + speed * self.time).rem_euclid(span) - margin;
+                (x, h * height)
+            }
+        }
+    }
+
+    /// Color of the pixel at `(dx, dy)` from the center of `body`, if the
+    /// body covers it. Distances are in units of the body's radius.
+    fn pixel(&self, kind: BodyKind, dx: f32, dy: f32, theme: &Theme) -> Option<Color> {
+        let d2 = dx * dx + dy * dy;
+        let ring = || {
+            // Ring tilted by ~20° and seen at a shallow angle
+            let (sin, cos) = 0.35f32.sin_cos();
+            let u = dx * cos + dy * sin;
+            let v = (dy * cos - dx * sin) * 3.5;
+            let r = (u * u + v * v).sqrt();
+            // Hidden where the planet is in front of its far half
+            let behind = v < 0.0 && d2 <= 1.0;
+            ((1.35..2.1).contains(&r) && !behind).then(|| {
+                let shade = if (1.65..1.75).contains(&r) { 0.4 } else { 0.8 };
+                lerp_color(theme.bg, theme.star_dim, shade * BODY_STRENGTH)
+            })
+        };
+        if kind == BodyKind::Ringed {
+            if let Some(color) = ring() {
+                return Some(color);
+            }
+        }
+        if d2 > 1.0 {
+            return None;
+        }
+
+        // Surface normal on a unit sphere, facing the viewer
+        let normal = (dx, dy, (1.0 - d2).sqrt());
+        let light = match kind {
+            // The sun is behind the moon at new moon and behind the viewer
+            // at full; waxing moons are lit from the right
+            BodyKind::Moon => {
+                let angle = self.phase * TAU;
+                (angle.sin(), 0.0, -angle.cos())
+            }
+            _ => (-0.5, -0.4, 0.77),
+        };
+        let lit = (normal.0 * light.0 + normal.1 * light.1 + normal.2 * light.2).max(0.0);
+        let (base, brightness) = match kind {
+            BodyKind::Moon => {
+                let cratered = CRATERS.iter().any(|&(cx, cy, r)| {
+                    let (x, y) = (dx - cx, dy - cy);
+                    x * x + y * y < r * r
+                });
+                let texture = if cratered { 0.7 } else { 1.0 };
+                (theme.star_bright, (lit * texture).max(EARTHSHINE))
+            }
+            BodyKind::Ringed => (theme.accent, 0.15 + 0.85 * lit),
+            BodyKind::Banded => {
+                let bands = 0.85 + 0.15 * (dy * 9.0).sin();
+                (theme.secondary, (0.15 + 0.85 * lit) * bands)
+            }
+        };
+        Some(lerp_color(theme.bg, base, brightness * BODY_STRENGTH))
+    }
+}
+
+impl Effect for Planets {
+    fn z_order(&self) -> i32 {
+        -17
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.time += dt;
+        self.phase_refresh -= dt;
+        if self.phase_refresh <= 0.0 {
+            self.phase_refresh = PHASE_REFRESH_SECONDS;
+            self.phase = astro::moon_phase(Utc::now()) as f32;
+        }
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        let scale = Self::scale(self.height);
+        for body in &self.bodies {
+            let radius = body.radius * scale;
+            // Rings reach a little over twice the planet's radius
+            let reach = if body.kind == BodyKind::Ringed {
+                radius * 2.2
+            } else {
+                radius
+            };
+            let (cx, cy) = self.position(body);
+            let (x0, x1) = ((cx - reach).floor(), (cx + reach).ceil());
+            let (row0, row1) = (((cy - reach) / 2.0).floor(), ((cy + reach) / 2.0).ceil());
+            for row in row0 as i32..=row1 as i32 {
+                for x in x0 as i32..=x1 as i32 {
+                    if x < area.x as i32
+                        || row < area.y as i32
+                        || x >= (area.x + area.width) as i32
+                        || row >= (area.y + area.height) as i32
+                    {
+                        continue;
+                    }
+                    // Sample the middle of the cell's two pixels
+                    let sample = |py: f32| {
+                        let dx = (x as f32 + 0.5 - cx) / radius;
+                        let dy = (py + 0.5 - cy) / radius;
+                        self.pixel(body.kind, dx, dy, ctx.theme)
+                    };
+                    let top = sample(row as f32 * 2.0);
+                    let bottom = sample(row as f32 * 2.0 + 1.0);
+                    let Some(cell) = buf.cell_mut((x as u16, row as u16)) else {
+                        continue;
+                    };
+                    match (top, bottom) {
+                        (Some(top), Some(bottom)) => {
+                            cell.set_char('▀').set_fg(top).set_bg(bottom);
+                        }
+                        (Some(top), None) => {
+                            cell.set_char('▀').set_fg(top);
+                        }
+                        (None, Some(bottom)) => {
+                            cell.set_char('▄').set_fg(bottom);
+                        }
+                        (None, None) => {}
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorMode;
+    use crate::effects::{ClearZone, DigitMask};
+
+    fn render(planets: &mut Planets) -> Buffer {
+        let theme = Theme::luna();
+        let ctx = RenderContext {
+            theme: &theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::default(),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        };
+        let area = Rect::new(0, 0, planets.width, planets.height);
+        let mut buf = Buffer::empty(area);
+        planets.render(&mut buf, area, &ctx);
+        buf
+    }
+
+    /// Summed brightness of the moon's left and right halves.
+    fn moon_halves(planets: &Planets) -> (f32, f32) {
+        let theme = Theme::luna();
+        let (mut left, mut right) = (0.0, 0.0);
+        for i in 0..20 {
+            for j in 0..20 {
+                let (dx, dy) = (i as f32 / 10.0 - 0.95, j as f32 / 10.0 - 0.95);
+                let Some(Color::Rgb(r, g, b)) = planets.pixel(BodyKind::Moon, dx, dy, &theme)
+                else {
+                    continue;
+                };
+                let v = r as f32 + g as f32 + b as f32;
+                if dx < 0.0 {
+                    left += v;
+                } else {
+                    right += v;
+                }
+            }
+        }
+        (left, right)
+    }
+
+    #[test]
+    fn test_moon_follows_phase() {
+        let mut planets = Planets::new(80, 24, Some(1));
+        planets.phase = 0.25;
+        let (left, right) = moon_halves(&planets);
+        assert!(right > left * 1.5, "a waxing moon is lit on the right");
+        planets.phase = 0.75;
+        let (left, right) = moon_halves(&planets);
+        assert!(left > right * 1.5, "a waning moon is lit on the left");
+        planets.phase = 0.5;
+        let full = moon_halves(&planets);
+        planets.phase = 0.0;
+        let new = moon_halves(&planets);
+        assert!(full.0 + full.1 > (new.0 + new.1) * 4.0);
+    }
+
+    #[test]
+    fn test_renders_half_block_sprites() {
+        let mut planets = Planets::new(80, 24, Some(4));
+        let buf = render(&mut planets);
+        let blocks = buf
+            .content()
+            .iter()
+            .filter(|c| c.symbol() == "▀" || c.symbol() == "▄")
+            .count();
+        assert!(blocks > 10, "only {blocks} sprite cells");
+    }
+
+    #[test]
+    fn test_bodies_move() {
+        let mut planets = Planets::new(80, 24, Some(2));
+        let before: Vec<_> = planets.bodies.iter().map(|b| planets.position(b)).collect();
+        planets.tick(5.0);
+        let after: Vec<_> = planets.bodies.iter().map(|b| planets.position(b)).collect();
+        for (a, b) in before.iter().zip(&after) {
+            assert_ne!(a, b);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_with_seed() {
+        let a = Planets::new(80, 24, Some(8));
+        let b = Planets::new(80, 24, Some(8));
+        assert_eq!(a.bodies, b.bodies);
+    }
+}
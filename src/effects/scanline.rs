@@ -1,16 +1,92 @@
-/// Scanline effect: subtle horizontal lines that drift slowly.
+//! Scanline effect: subtle horizontal lines that drift slowly.
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::{Effect, RenderContext};
+use crate::color::ColorMode;
+use crate::theme::color_rgb;
+
+/// Default peak opacity of a scanline.
+pub const DEFAULT_OPACITY: f32 = 0.15;
+
+/// Default breathing speed (phase radians per second).
+pub const DEFAULT_SPEED: f32 = 0.5;
+
+/// The scanline overlay, drawn over the clock.
+#[derive(Debug, Clone, Copy)]
+pub struct Scanlines {
+    /// Peak opacity (0.0-1.0)
+    pub opacity: f32,
+    /// Breathing speed; 0 holds the lines still
+    pub speed: f32,
+}
 
 /// Determine if a given row should have a scanline overlay at a given tick.
-/// Returns an opacity value (0.0 = no scanline, up to 0.3 = subtle darkening).
-pub fn scanline_opacity(row: u16, tick: f32) -> f32 {
+/// Returns an opacity value (0.0 = no scanline, up to `peak` = darkest);
+/// `speed` sets how fast the lines breathe.
+pub fn scanline_opacity(row: u16, tick: f32, peak: f32, speed: f32) -> f32 {
     // Every other row gets a faint scanline
-    if row % 2 == 0 {
+    if row.is_multiple_of(2) {
         return 0.0;
     }
-    // Gentle breathing effect
-    let phase = (tick * 0.5 + row as f32 * 0.1).sin();
-    let opacity = 0.08 + phase.abs() * 0.07;
-    opacity
+    // Gentle breathing effect between about half and full strength
+    let phase = (tick * speed + row as f32 * 0.1).sin();
+    peak * (8.0 + phase.abs() * 7.0) / 15.0
+}
+
+impl Effect for Scanlines {
+    fn z_order(&self) -> i32 {
+        10
+    }
+
+    fn tick(&mut self, _dt: f32) {}
+
+    fn resize(&mut self, _width: u16, _height: u16) {}
+
+    /// Tint alternate rows toward the scanline color. Normally this darkens
+    /// the background; over a transparent background only drawn glyphs are
+    /// tinted. At 16 colors the subtle tint would collapse into the
+    /// background, and mono has no tint at all, so nothing is drawn there.
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        if matches!(ctx.color_mode, ColorMode::Ansi16 | ColorMode::Mono) {
+            return;
+        }
+        for y in area.y..area.y + area.height {
+            let opacity = scanline_opacity(y, ctx.elapsed, self.opacity, self.speed);
+            if opacity > 0.01 {
+                let (sr, sg, sb) = color_rgb(ctx.theme.scanline).unwrap_or((0, 0, 0));
+                for x in area.x..area.x + area.width {
+                    if let Some(cell) = buf.cell_mut((x, y)) {
+                        if ctx.transparent {
+                            if cell.symbol() != " " {
+                                if let Some((fr, fg, fb)) = color_rgb(cell.fg) {
+                                    let tinted = Color::Rgb(
+                                        lerp(fr, sr, opacity),
+                                        lerp(fg, sg, opacity),
+                                        lerp(fb, sb, opacity),
+                                    );
+                                    cell.set_fg(tinted);
+                                }
+                            }
+                            continue;
+                        }
+                        // Darken the existing bg slightly toward scanline color
+                        if let Some((br, bg_c, bb)) = color_rgb(cell.bg) {
+                            let nr = lerp(br, sr, opacity);
+                            let ng = lerp(bg_c, sg, opacity);
+                            let nb = lerp(bb, sb, opacity);
+                            cell.set_style(cell.style().bg(Color::Rgb(nr, ng, nb)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lerp(a: u8, b: u8, t: f32) -> u8 {
+    let v = a as f32 + (b as f32 - a as f32) * t;
+    v.clamp(0.0, 255.0) as u8
 }
 
 #[cfg(test)]
@@ -19,14 +95,35 @@ mod tests {
 
     #[test]
     fn test_even_rows_no_scanline() {
-        assert_eq!(scanline_opacity(0, 0.0), 0.0);
-        assert_eq!(scanline_opacity(2, 1.0), 0.0);
-        assert_eq!(scanline_opacity(100, 5.0), 0.0);
+        assert_eq!(
+            scanline_opacity(0, 0.0, DEFAULT_OPACITY, DEFAULT_SPEED),
+            0.0
+        );
+        assert_eq!(
+            scanline_opacity(2, 1.0, DEFAULT_OPACITY, DEFAULT_SPEED),
+            0.0
+        );
+        assert_eq!(
+            scanline_opacity(100, 5.0, DEFAULT_OPACITY, DEFAULT_SPEED),
+            0.0
+        );
     }
 
     #[test]
     fn test_odd_rows_have_scanline() {
-        let o = scanline_opacity(1, 0.0);
+        let o = scanline_opacity(1, 0.0, DEFAULT_OPACITY, DEFAULT_SPEED);
         assert!(o > 0.0 && o < 0.5);
     }
+
+    #[test]
+    fn test_opacity_and_speed_tunables() {
+        assert_eq!(scanline_opacity(1, 3.0, 0.0, DEFAULT_SPEED), 0.0);
+        let o = scanline_opacity(1, 3.0, 0.3, DEFAULT_SPEED);
+        assert!(o > 0.15 && o <= 0.3);
+        // Zero speed: the lines hold still over time
+        assert_eq!(
+            scanline_opacity(3, 0.0, DEFAULT_OPACITY, 0.0),
+            scanline_opacity(3, 100.0, DEFAULT_OPACITY, 0.0)
+        );
+    }
 }
@@ -1,4 +1,15 @@
+use std::f32::consts::TAU;
+
+use chrono::{DateTime, Utc};
 use fastrand::Rng;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::constellation::SkyView;
+use super::meteor_shower::MeteorShower;
+use super::starfield::streak_char;
+use super::{Effect, RenderContext};
+use crate::astro::Location;
+use crate::theme::color_rgb;
 
 /// A shooting star that streaks across the screen.
 #[derive(Debug, Clone)]
@@ -9,6 +20,8 @@ pub struct ShootingStar {
     /// Velocity (cells/sec)
     pub vx: f32,
     pub vy: f32,
+    /// How fast the path bends, in radians/sec (0 = straight)
+    pub turn: f32,
     /// Trail length in cells
     pub trail_len: u8,
     /// Remaining lifetime in seconds
@@ -20,16 +33,70 @@ pub struct ShootingStar {
 pub struct ShootingStarManager {
     pub active: Vec<ShootingStar>,
     cooldown: f32,
+    /// Seconds between shooting stars (min, max)
+    interval: (f32, f32),
+    /// Speed range in cells/sec (min, max)
+    speed: (f32, f32),
+    /// Direction range in degrees counter-clockwise from rightward (min, max)
+    angle: (f32, f32),
+    /// Curvature (0.0-1.0)
+    curve: f32,
+    /// Observer, for placing shower radiants in the sky
+    location: Option<Location>,
+    /// Shower chosen with `--meteor-shower`, which overrides the calendar
+    forced_shower: Option<MeteorShower>,
+    /// Shower under way and its activity (0.0-1.0)
+    shower: Option<(MeteorShower, f32)>,
+    /// When the calendar and the sky were last worked out
+    now: DateTime<Utc>,
+    /// Sky seen at `now`, for placing shower radiants
+    sky: SkyView,
+    /// Seconds until the calendar and the sky are worked out again
+    refresh: f32,
     rng: Rng,
     width: u16,
     height: u16,
 }
 
-/// Characters for the shooting star trail (head → tail).
-pub const TRAIL_CHARS: [char; 4] = ['━', '─', '╌', '·'];
+/// Default gap between shooting stars: 5-15 seconds (rare).
+pub const DEFAULT_INTERVAL: (f32, f32) = (5.0, 15.0);
+
+/// Shortest allowed gap between shooting stars, in seconds.
+pub const MIN_INTERVAL: f32 = 0.5;
+
+/// Default speed range in cells/sec.
+pub const DEFAULT_SPEED: (f32, f32) = (20.0, 50.0);
+
+/// Default direction range: any way across the sky.
+pub const DEFAULT_ANGLE: (f32, f32) = (0.0, 360.0);
+
+/// Default curvature: straight paths.
+pub const DEFAULT_CURVE: f32 = 0.0;
+
+/// Turn rate at full curvature, in radians/sec.
+const MAX_TURN: f32 = 1.2;
+
+/// Seconds over which a dying shooting star fades out.
+const FADE_SECONDS: f32 = 0.3;
+
+/// How often the shower calendar and the sky are updated, in seconds.
+const REFRESH_SECONDS: f32 = 60.0;
+
+/// Trail characters (behind the head → tail) for each line direction.
+const TRAIL_CHARS: [(char, [char; 4]); 4] = [
+    ('─', ['━', '─', '╌', '·']),
+    ('│', ['┃', '│', '╎', '·']),
+    ('╲', ['╲', '╲', '╲', '·']),
+    ('╱', ['╱', '╱', '╱', '·']),
+];
+
+/// Braille dot bits by (column, row) within a cell.
+const BRAILLE_DOTS: [[u32; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
 
 impl ShootingStarManager {
-    pub fn new(width: u16, height: u16, seed: Option<u64>) -> Self {
+    /// Shooting stars for a `width` x `height` sky, following the shower
+    /// calendar from `now`.
+    pub fn new(width: u16, height: u16, seed: Option<u64>, now: DateTime<Utc>) -> Self {
         let mut rng = match seed {
             Some(s) => Rng::with_seed(s.wrapping_add(0xDEAD)),
             None => Rng::new(),
@@ -37,80 +104,481 @@ impl ShootingStarManager {
         Self {
             active: Vec::new(),
             cooldown: 3.0 + rng.f32() * 5.0, // first one after 3-8 seconds
+            interval: DEFAULT_INTERVAL,
+            speed: DEFAULT_SPEED,
+            angle: DEFAULT_ANGLE,
+            curve: DEFAULT_CURVE,
+            location: None,
+            forced_shower: None,
+            shower: MeteorShower::current(now),
+            now,
+            sky: SkyView::new(None, now, width, height),
+            refresh: REFRESH_SECONDS,
             rng,
             width,
             height,
         }
     }
 
+    /// Use a custom gap between shooting stars, in seconds.
+    pub fn with_interval(mut self, (min, max): (f32, f32)) -> Self {
+        self.interval = (min, max);
+        self.cooldown = self.cooldown.min(max);
+        self
+    }
+
+    /// Use a custom speed range, in cells/sec.
+    pub fn with_speed(mut self, speed: (f32, f32)) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Send shooting stars in directions within `(min, max)` degrees,
+    /// counter-clockwise from rightward (0 = right, 90 = up).
+    pub fn with_angle(mut self, angle: (f32, f32)) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// Bend paths by up to `curve` (0.0-1.0) of the maximum turn rate.
+    pub fn with_curve(mut self, curve: f32) -> Self {
+        self.curve = curve;
+        self
+    }
+
+    /// Place shower radiants in the sky as seen from `location`.
+    pub fn with_location(mut self, location: Option<Location>) -> Self {
+        self.location = location;
+        self.sky = SkyView::new(location, self.now, self.width, self.height);
+        self
+    }
+
+    /// Run `shower` at its peak regardless of the date; `None` follows the
+    /// calendar.
+    pub fn with_shower(mut self, shower: Option<MeteorShower>) -> Self {
+        self.forced_shower = shower;
+        if let Some(shower) = shower {
+            self.shower = Some((shower, 1.0));
+        }
+        self
+    }
+
+    /// How many times more often shooting stars appear right now.
+    fn rate(&self) -> f32 {
+        self.shower
+            .map_or(1.0, |(shower, activity)| shower.rate(activity))
+    }
+
+    /// Uniform random value in `min..=max`.
+    fn random_in(&mut self, (min, max): (f32, f32)) -> f32 {
+        min + self.rng.f32() * (max - min)
+    }
+
+    /// Random turn rate within the configured curvature, either way.
+    fn random_turn(&mut self) -> f32 {
+        self.curve * MAX_TURN * (self.rng.f32() * 2.0 - 1.0)
+    }
+
+    /// Spawn a shooting star: during a shower most come from its radiant,
+    /// the rest are sporadic as on any other night.
+    fn spawn(&mut self) {
+        let rate = self.rate();
+        if let Some((shower, _)) = self.shower {
+            if self.rng.f32() < 1.0 - 1.0 / rate && self.spawn_from_radiant(shower) {
+                return;
+            }
+        }
+        self.spawn_sporadic();
+    }
+
+    /// Spawn a meteor heading straight away from the shower's radiant, as
+    /// placed by the constellation view. Returns false when the radiant is
+    /// below the horizon. A forced shower with its radiant out of sight
+    /// rains down from above the top of the screen instead.
+    fn spawn_from_radiant(&mut self, shower: MeteorShower) -> bool {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let info = shower.info();
+        let radiant = match self.sky.project(info.ra, info.dec) {
+            Some(radiant) => radiant,
+            None if self.forced_shower.is_some() => (w / 2.0, -h * 0.3),
+            None => return false,
+        };
+        // Meteors appear some way out from the radiant; look for a start
+        // on screen a few times before giving up
+        for _ in 0..8 {
+            let angle = self.rng.f32() * TAU;
+            // Rows are about twice as tall as columns are wide
+            let (dx, dy) = (angle.cos(), angle.sin() * 0.5);
+            let distance = 2.0 + self.rng.f32() * w.max(h * 2.0) * 0.4;
+            let (x, y) = (radiant.0 + dx * distance, radiant.1 + dy * distance);
+            if x < 0.0 || y < 0.0 || x >= w || y >= h {
+                continue;
+            }
+            let speed = self.random_in(self.speed);
+            let turn = self.random_turn();
+            self.active.push(ShootingStar {
+                x,
+                y,
+                vx: dx * speed,
+                vy: dy * speed,
+                turn,
+                trail_len: 4 + self.rng.u8(0..4),
+                life: 1.0 + self.rng.f32(),
+            });
+            return true;
+        }
+        false
+    }
+
+    /// Spawn a shooting star entering from the edge it is heading away
+    /// from, aimed at a point in the upper part of the sky.
+    fn spawn_sporadic(&mut self) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let speed = self.random_in(self.speed);
+        let angle = self.random_in(self.angle).to_radians();
+        // Rows are about twice as tall as columns are wide
+        let (vx, vy) = (angle.cos() * speed, -angle.sin() * speed / 2.0);
+        let target = (
+            w * (0.1 + self.rng.f32() * 0.8),
+            h * (0.1 + self.rng.f32() * 0.6),
+        );
+        // Seconds back along the path to the edge of the screen
+        let to_edge = |p: f32, v: f32, size: f32| match v {
+            v if v > 0.0 => p / v,
+            v if v < 0.0 => (size - p) / -v,
+            _ => f32::INFINITY,
+        };
+        let t = to_edge(target.0, vx, w).min(to_edge(target.1, vy, h));
+        let turn = self.random_turn();
+        self.active.push(ShootingStar {
+            x: target.0 - vx * t,
+            y: target.1 - vy * t,
+            vx,
+            vy,
+            turn,
+            trail_len: 4 + self.rng.u8(0..4),
+            life: t + 0.5 + self.rng.f32(),
+        });
+    }
+
+    /// Trail cells behind the head, nearest first, with glyphs following
+    /// the path's direction at each cell. Curved paths are traced back
+    /// along their arc.
+    pub fn trail_positions(star: &ShootingStar) -> Vec<(i32, i32, char)> {
+        // Work in square units: a row is two columns tall
+        let (mut vx, mut vy) = (star.vx, star.vy * 2.0);
+        let speed = vx.hypot(vy).max(0.01);
+        const STEP: f32 = 0.5;
+        let (sin, cos) = (-star.turn * STEP / speed).sin_cos();
+        let (mut x, mut y) = (star.x, star.y);
+        let mut last = (x.floor() as i32, y.floor() as i32);
+        let mut positions = Vec::new();
+        for _ in 0..star.trail_len as usize * 6 {
+            x -= vx / speed * STEP;
+            y -= vy / speed * STEP / 2.0;
+            (vx, vy) = (vx * cos - vy * sin, vx * sin + vy * cos);
+            let cell = (x.floor() as i32, y.floor() as i32);
+            if cell == last {
+                continue;
+            }
+            last = cell;
+            let ch = trail_char(vx, vy / 2.0, positions.len());
+            positions.push((cell.0, cell.1, ch));
+            if positions.len() == star.trail_len as usize {
+                break;
+            }
+        }
+        positions
+    }
+
+    /// The head as a braille character: a dot at its sub-cell position and
+    /// a few more behind it, as far as they stay within the cell.
+    pub fn head(star: &ShootingStar) -> (i32, i32, char) {
+        let cell = (star.x.floor() as i32, star.y.floor() as i32);
+        // Braille cells are 2 dots wide and 4 tall
+        let (dx, dy) = (star.x * 2.0, star.y * 4.0);
+        let (vx, vy) = (star.vx * 2.0, star.vy * 4.0);
+        let len = vx.hypot(vy).max(0.01);
+        let mut bits = 0;
+        for k in 0..4 {
+            let (px, py) = (dx - vx / len * k as f32, dy - vy / len * k as f32);
+            let (col, row) = (px.floor() as i32, py.floor() as i32);
+            if col.div_euclid(2) == cell.0 && row.div_euclid(4) == cell.1 {
+                bits |= BRAILLE_DOTS[col.rem_euclid(2) as usize][row.rem_euclid(4) as usize];
+            }
+        }
+        (cell.0, cell.1, char::from_u32(0x2800 + bits).unwrap_or('•'))
+    }
+}
+
+/// Trail glyph `i` cells behind the head for a path heading `(vx, vy)`.
+fn trail_char(vx: f32, vy: f32, i: usize) -> char {
+    let line = streak_char(vx, vy);
+    let chars = TRAIL_CHARS
+        .iter()
+        .find(|(c, _)| *c == line)
+        .map_or(TRAIL_CHARS[0].1, |(_, chars)| *chars);
+    chars[i.min(chars.len() - 1)]
+}
+
+impl Effect for ShootingStarManager {
+    fn z_order(&self) -> i32 {
+        -10
+    }
+
     /// Advance the shooting star animation.
-    pub fn tick(&mut self, dt: f32) {
+    fn tick(&mut self, dt: f32) {
         // Update existing
+        let (w, h) = (self.width as f32, self.height as f32);
         self.active.retain_mut(|s| {
+            if s.turn != 0.0 {
+                // Turn in square units: a row is two columns tall
+                let (sin, cos) = (s.turn * dt).sin_cos();
+                let (vx, vy) = (s.vx, s.vy * 2.0);
+                s.vx = vx * cos - vy * sin;
+                s.vy = (vx * sin + vy * cos) / 2.0;
+            }
             s.x += s.vx * dt;
             s.y += s.vy * dt;
             s.life -= dt;
-            s.life > 0.0
-                && s.x > -(s.trail_len as f32)
-                && s.x < self.width as f32 + 10.0
-                && s.y > -2.0
-                && s.y < self.height as f32 + 2.0
+            let margin = s.trail_len as f32 + 2.0;
+            s.life > 0.0 && s.x > -margin && s.x < w + margin && s.y > -margin && s.y < h + margin
         });
 
+        self.refresh -= dt;
+        if self.refresh <= 0.0 {
+            self.refresh = REFRESH_SECONDS;
+            self.now = Utc::now();
+            self.sky = SkyView::new(self.location, self.now, self.width, self.height);
+            if self.forced_shower.is_none() {
+                self.shower = MeteorShower::current(self.now);
+            }
+        }
+
         // Spawn new
         self.cooldown -= dt;
         if self.cooldown <= 0.0 {
             self.spawn();
-            // Next one in 5-15 seconds (rare)
-            self.cooldown = 5.0 + self.rng.f32() * 10.0;
+            self.cooldown = self.random_in(self.interval) / self.rate();
         }
     }
 
-    fn spawn(&mut self) {
-        let w = self.width as f32;
-        let h = self.height as f32;
-        // Start from right side, streak left-ish
-        let x = w * 0.6 + self.rng.f32() * w * 0.4;
-        let y = self.rng.f32() * h * 0.5; // upper half
-        let speed = 20.0 + self.rng.f32() * 30.0;
-        let angle = 0.1 + self.rng.f32() * 0.3; // slight downward angle
-        self.active.push(ShootingStar {
-            x,
-            y,
-            vx: -speed,
-            vy: speed * angle,
-            trail_len: 4 + self.rng.u8(0..4),
-            life: 1.5 + self.rng.f32(),
-        });
-    }
-
-    pub fn resize(&mut self, width: u16, height: u16) {
+    fn resize(&mut self, width: u16, height: u16) {
         self.width = width;
         self.height = height;
+        self.sky = SkyView::new(self.location, self.now, width, height);
     }
 
-    /// Get trail positions for rendering (from head to tail).
-    pub fn trail_positions(star: &ShootingStar) -> Vec<(u16, u16, char)> {
-        let mut positions = Vec::new();
-        let dx = if star.vx.abs() > 0.01 {
-            -star.vx / star.vx.abs()
-        } else {
-            1.0
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        let (r, g, b) = color_rgb(ctx.theme.shooting_star).unwrap_or((200, 220, 255));
+        let shade = |fade: f32| {
+            Color::Rgb(
+                (r as f32 * fade) as u8,
+                (g as f32 * fade) as u8,
+                (b as f32 * fade) as u8,
+            )
+        };
+        let mut draw = |x: i32, y: i32, ch: char, fade: f32| {
+            let inside = x >= area.x as i32
+                && y >= area.y as i32
+                && x < (area.x + area.width) as i32
+                && y < (area.y + area.height) as i32;
+            if !inside {
+                return;
+            }
+            let visibility = ctx.star_visibility(x as u16, y as u16);
+            if visibility == 0.0 {
+                return;
+            }
+            if let Some(cell) = buf.cell_mut((x as u16, y as u16)) {
+                cell.set_char(ch).set_fg(shade(fade * visibility));
+            }
+        };
+        for star in &self.active {
+            // Dying stars fade out rather than vanish
+            let life = (star.life / FADE_SECONDS).min(1.0);
+            let trail = Self::trail_positions(star);
+            // Fade trail: bright behind the head, dim at the tail
+            for (i, &(x, y, ch)) in trail.iter().enumerate().rev() {
+                let fade = 1.0 - (i + 1) as f32 / (trail.len() + 1) as f32;
+                draw(x, y, ch, fade * life);
+            }
+            let (x, y, ch) = Self::head(star);
+            draw(x, y, ch, life);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Early March: no shower under way.
+    fn quiet_night() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_speed_range_is_respected() {
+        let mut m = ShootingStarManager::new(80, 24, Some(3), quiet_night())
+            .with_speed((7.0, 7.0))
+            .with_angle((180.0, 180.0));
+        m.spawn();
+        assert_eq!(m.active[0].vx, -7.0);
+    }
+
+    #[test]
+    fn test_enters_from_the_edge_it_leaves() {
+        let mut m = ShootingStarManager::new(80, 24, Some(6), quiet_night());
+        for _ in 0..50 {
+            m.spawn();
+        }
+        for s in &m.active {
+            // On an edge, and moving into the screen from it
+            let entering = [
+                (s.x, s.vx > 0.0),
+                (80.0 - s.x, s.vx < 0.0),
+                (s.y, s.vy > 0.0),
+                (24.0 - s.y, s.vy < 0.0),
+            ];
+            assert!(
+                entering.iter().any(|&(d, inward)| d.abs() < 0.01 && inward),
+                "starts at ({}, {}) heading ({}, {})",
+                s.x,
+                s.y,
+                s.vx,
+                s.vy
+            );
+        }
+        let leftward = m.active.iter().filter(|s| s.vx < 0.0).count();
+        assert!(leftward > 5 && leftward < 45, "directions are mixed");
+    }
+
+    #[test]
+    fn test_angle_range_limits_direction() {
+        let mut m =
+            ShootingStarManager::new(80, 24, Some(2), quiet_night()).with_angle((80.0, 100.0));
+        for _ in 0..20 {
+            m.spawn();
+        }
+        // Heading up: rows decrease
+        assert!(m
+            .active
+            .iter()
+            .all(|s| s.vy < 0.0 && s.vx.abs() < -s.vy * 2.0));
+    }
+
+    fn star(vx: f32, vy: f32, turn: f32) -> ShootingStar {
+        ShootingStar {
+            x: 40.3,
+            y: 12.6,
+            vx,
+            vy,
+            turn,
+            trail_len: 6,
+            life: 2.0,
+        }
+    }
+
+    #[test]
+    fn test_trail_follows_slope() {
+        let glyphs = |s: &ShootingStar| -> String {
+            ShootingStarManager::trail_positions(s)
+                .iter()
+                .map(|p| p.2)
+                .collect()
         };
-        let dy = if star.vy.abs() > 0.01 {
-            -star.vy / star.vy.abs()
-        } else {
-            0.0
+        assert!(glyphs(&star(-30.0, 0.0, 0.0)).starts_with('━'));
+        assert!(glyphs(&star(0.0, 15.0, 0.0)).starts_with('┃'));
+        assert!(glyphs(&star(20.0, 10.0, 0.0)).starts_with('╲'));
+        assert!(glyphs(&star(-20.0, 10.0, 0.0)).starts_with('╱'));
+        let trail = ShootingStarManager::trail_positions(&star(-30.0, 0.0, 0.0));
+        assert_eq!(trail.len(), 6);
+        assert!(
+            trail.iter().all(|p| p.0 > 40 && p.1 == 12),
+            "behind the head"
+        );
+    }
+
+    #[test]
+    fn test_head_has_sub_cell_position() {
+        let (x, y, left) = ShootingStarManager::head(&ShootingStar {
+            x: 40.2,
+            ..star(0.0, 10.0, 0.0)
+        });
+        let (_, _, right) = ShootingStarManager::head(&ShootingStar {
+            x: 40.7,
+            ..star(0.0, 10.0, 0.0)
+        });
+        assert_eq!((x, y), (40, 12));
+        assert_ne!(left, right);
+        assert!(('\u{2801}'..='\u{28ff}').contains(&left));
+    }
+
+    #[test]
+    fn test_curve_bends_the_path() {
+        let mut m = ShootingStarManager::new(80, 24, Some(1), quiet_night()).with_curve(1.0);
+        m.active.push(star(-30.0, 0.0, 1.0));
+        m.tick(0.2);
+        assert!(m.active[0].vy.abs() > 0.5);
+        let speed = m.active[0].vx.hypot(m.active[0].vy * 2.0);
+        assert!((speed - 30.0).abs() < 0.01, "turning keeps the speed");
+    }
+
+    #[test]
+    fn test_interval_controls_frequency() {
+        let count = |interval| {
+            let mut m =
+                ShootingStarManager::new(200, 50, Some(9), quiet_night()).with_interval(interval);
+            m.refresh = f32::INFINITY;
+            let mut spawned = 0;
+            for _ in 0..600 {
+                let before = m.active.len();
+                m.tick(0.1);
+                spawned += m.active.len().saturating_sub(before);
+            }
+            spawned
         };
+        assert!(count((1.0, 1.0)) > count((20.0, 30.0)));
+    }
 
-        for i in 0..star.trail_len {
-            let tx = star.x + dx * i as f32;
-            let ty = star.y + dy * i as f32 * 0.3; // compressed vertical
-            if tx >= 0.0 && ty >= 0.0 {
-                let ch = TRAIL_CHARS[(i as usize).min(TRAIL_CHARS.len() - 1)];
-                positions.push((tx as u16, ty as u16, ch));
+    #[test]
+    fn test_forced_shower_raises_rate() {
+        let count = |shower| {
+            let mut m =
+                ShootingStarManager::new(200, 50, Some(9), quiet_night()).with_shower(shower);
+            // Keep the calendar at the quiet night
+            m.refresh = f32::INFINITY;
+            let mut spawned = 0;
+            for _ in 0..600 {
+                let before = m.active.len();
+                m.tick(0.1);
+                spawned += m.active.len().saturating_sub(before);
             }
+            spawned
+        };
+        assert!(count(Some(MeteorShower::Geminids)) > count(None) * 3);
+    }
+
+    #[test]
+    fn test_shower_meteors_leave_the_radiant() {
+        let mut m = ShootingStarManager::new(120, 40, Some(5), quiet_night())
+            .with_shower(Some(MeteorShower::Geminids))
+            .with_speed((30.0, 30.0));
+        let info = MeteorShower::Geminids.info();
+        let radiant = SkyView::new(None, quiet_night(), 120, 40)
+            .project(info.ra, info.dec)
+            .unwrap();
+        for _ in 0..20 {
+            assert!(m.spawn_from_radiant(MeteorShower::Geminids));
+        }
+        for star in &m.active {
+            let (ox, oy) = (star.x - radiant.0, star.y - radiant.1);
+            assert!(
+                ox * star.vx + oy * star.vy > 0.0,
+                "moving away from the radiant"
+            );
         }
-        positions
     }
 }
@@ -1,5 +1,8 @@
 use fastrand::Rng;
-use ratatui::style::Color;
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use super::{Effect, EffectEvent, RenderContext};
+use crate::theme::lerp_color;
 
 /// A single star with position and parallax layer.
 #[derive(Debug, Clone)]
@@ -12,6 +15,62 @@ pub struct Star {
     pub phase: f32,
 }
 
+/// How the starfield moves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StarMode {
+    /// Stars drift sideways in parallax layers
+    Drift,
+    /// Stars stream outward from the center, hyperspace style
+    Warp,
+}
+
+/// Which way drifting stars move.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StarDirection {
+    /// Degrees counter-clockwise from rightward: 0 = right, 90 = up,
+    /// 180 = left, 270 = down
+    Angle(f32),
+    /// Stars hold their position (they still twinkle)
+    Still,
+}
+
+impl StarDirection {
+    /// Parse an angle in degrees or one of `left`, `right`, `up`, `down`,
+    /// `still`.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let angle = match s.trim().to_lowercase().as_str() {
+            "still" | "none" => return Ok(Self::Still),
+            "right" => 0.0,
+            "up" => 90.0,
+            "left" => 180.0,
+            "down" => 270.0,
+            other => other.parse::<f32>().map_err(|_| {
+                format!("`{s}` is not an angle in degrees or one of: left, right, up, down, still")
+            })?,
+        };
+        if !angle.is_finite() {
+            return Err(format!("`{s}` is not a finite angle"));
+        }
+        Ok(Self::Angle(angle.rem_euclid(360.0)))
+    }
+
+    /// Unit drift vector in cells per cell of travel. Rows are about twice
+    /// as tall as columns are wide, so the vertical part is halved to keep
+    /// the on-screen speed the same in every direction.
+    fn vector(self) -> (f32, f32) {
+        match self {
+            Self::Angle(deg) => {
+                let rad = deg.to_radians();
+                (rad.cos(), -rad.sin() / 2.0)
+            }
+            Self::Still => (0.0, 0.0),
+        }
+    }
+}
+
+/// Stars drift leftward unless told otherwise.
+pub const DEFAULT_DIRECTION: StarDirection = StarDirection::Angle(180.0);
+
 /// Manages the starfield background effect.
 #[derive(Debug, Clone)]
 pub struct Starfield {
@@ -19,11 +78,51 @@ pub struct Starfield {
     rng: Rng,
     width: u16,
     height: u16,
+    density: u32,
+    /// Drift speed of the far, mid and near layers in cells/sec
+    parallax: [f32; 3],
+    /// Twinkle amplitude, as a fraction of full brightness
+    twinkle: f32,
+    mode: StarMode,
+    direction: StarDirection,
+    /// Multiplier applied to every layer's drift speed
+    speed: f32,
+    /// Seconds since warp was engaged, while a warp jump is running
+    jump: Option<f32>,
 }
 
+/// Default layer speeds: far=0.2, mid=0.5, near=1.0 cells/sec.
+pub const DEFAULT_PARALLAX: [f32; 3] = [0.2, 0.5, 1.0];
+
+/// Default twinkle amplitude: ±20% brightness.
+pub const DEFAULT_TWINKLE: f32 = 0.2;
+
 /// Characters used for stars at different layers.
 const STAR_CHARS: [char; 3] = ['·', '∘', '✦'];
 
+/// Warp intensity while cruising in [`StarMode::Warp`] (1.0 = full jump).
+const WARP_CRUISE: f32 = 0.25;
+
+/// Outward expansion rate per second at full warp, for the near layer.
+/// Moving at a rate proportional to the distance from the center is what
+/// makes stars appear to approach in perspective.
+const WARP_RATE: f32 = 3.0;
+
+/// Relative warp speed of the far, mid and near layers.
+const WARP_LAYER_SCALE: [f32; 3] = [0.45, 0.7, 1.0];
+
+/// A warp jump: seconds to accelerate, hold full speed, then slow down.
+const JUMP_RAMP: f32 = 1.0;
+const JUMP_HOLD: f32 = 2.5;
+
+/// Streak length is the distance covered in this many seconds...
+const STREAK_SECONDS: f32 = 0.1;
+/// ...capped at this many cells.
+const MAX_STREAK: f32 = 14.0;
+
+/// Below this warp intensity stars are drawn as points, not streaks.
+const STREAK_THRESHOLD: f32 = 0.05;
+
 impl Starfield {
     /// Create a new starfield filling the given dimensions.
     /// `density` is 0-100 controlling how many stars to generate.
@@ -38,16 +137,23 @@ impl Starfield {
             rng,
             width,
             height,
+            density,
+            parallax: DEFAULT_PARALLAX,
+            twinkle: DEFAULT_TWINKLE,
+            mode: StarMode::Drift,
+            direction: DEFAULT_DIRECTION,
+            speed: 1.0,
+            jump: None,
         };
-        sf.populate(density);
+        sf.populate();
         sf
     }
 
     /// Populate stars based on density (0-100).
-    fn populate(&mut self, density: u32) {
+    fn populate(&mut self) {
         let area = self.width as u32 * self.height as u32;
         // At density=100, ~1 star per 8 cells; at density=0, no stars
-        let count = (area * density) / 800;
+        let count = (area * self.density) / 800;
 
         self.stars.clear();
         self.stars.reserve(count as usize);
@@ -62,37 +168,102 @@ impl Starfield {
         }
     }
 
-    /// Advance the starfield animation by one tick.
-    /// `dt` is delta time in seconds.
-    pub fn tick(&mut self, dt: f32) {
-        let w = self.width as f32;
-        for star in &mut self.stars {
-            // Parallax speed: far=0.2, mid=0.5, near=1.0 cells/sec
-            let speed = match star.layer {
-                0 => 0.2,
-                1 => 0.5,
-                _ => 1.0,
-            };
-            star.x -= speed * dt;
-            if star.x < 0.0 {
-                star.x += w;
-                star.y = self.rng.f32() * self.height as f32;
-            }
-            // Advance twinkle phase
-            star.phase += dt * 2.0;
-            if star.phase > std::f32::consts::TAU {
-                star.phase -= std::f32::consts::TAU;
-            }
-        }
+    /// Use custom layer speeds (far, mid, near) in cells/sec.
+    pub fn with_parallax(mut self, parallax: [f32; 3]) -> Self {
+        self.parallax = parallax;
+        self
     }
 
-    /// Resize the starfield. Re-populates if dimensions changed.
-    pub fn resize(&mut self, width: u16, height: u16, density: u32) {
-        if width != self.width || height != self.height {
-            self.width = width;
-            self.height = height;
-            self.populate(density);
+    /// Use a custom twinkle amplitude (0 = steady stars).
+    pub fn with_twinkle(mut self, twinkle: f32) -> Self {
+        self.twinkle = twinkle;
+        self
+    }
+
+    /// Use a different motion mode.
+    pub fn with_mode(mut self, mode: StarMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Drift in `direction` at `speed` times the layer speeds.
+    pub fn with_drift(mut self, direction: StarDirection, speed: f32) -> Self {
+        self.direction = direction;
+        self.speed = speed;
+        self
+    }
+
+    /// Start a warp jump: accelerate into hyperspace for a few seconds,
+    /// then drop back to the normal motion. Engaging during a jump extends it.
+    pub fn engage_warp(&mut self) {
+        self.jump = Some(match self.jump {
+            Some(t) if t >= JUMP_RAMP => JUMP_RAMP,
+            Some(t) => t,
+            None => 0.0,
+        });
+    }
+
+    /// Current warp intensity, 0.0 (plain drift) to 1.0 (full jump).
+    pub fn warp_level(&self) -> f32 {
+        let cruise = match self.mode {
+            StarMode::Drift => 0.0,
+            StarMode::Warp => WARP_CRUISE,
+        };
+        let jump = match self.jump {
+            Some(t) if t < JUMP_RAMP => t / JUMP_RAMP,
+            Some(t) if t < JUMP_RAMP + JUMP_HOLD => 1.0,
+            Some(t) => 1.0 - (t - JUMP_RAMP - JUMP_HOLD) / JUMP_RAMP,
+            None => 0.0,
+        };
+        let jump = jump.clamp(0.0, 1.0);
+        // Smoothstep so speed eases in and out
+        cruise.max(jump * jump * (3.0 - 2.0 * jump))
+    }
+
+    /// Outward velocity of `star` in cells/sec at warp intensity `warp`.
+    /// Terminal cells are about twice as tall as wide, so distances are
+    /// measured with rows doubled to keep the motion circular on screen.
+    fn warp_velocity(&self, star: &Star, warp: f32) -> (f32, f32) {
+        let dx = star.x - self.width as f32 / 2.0;
+        let dy = (star.y - self.height as f32 / 2.0) * 2.0;
+        let dist = dx.hypot(dy).max(0.01);
+        let rate = WARP_RATE * WARP_LAYER_SCALE[(star.layer as usize).min(2)] * warp;
+        // The +1 keeps stars that start right at the center moving
+        let speed = (dist + 1.0) * rate;
+        (dx / dist * speed, dy / dist * speed / 2.0)
+    }
+
+    fn on_screen(&self, i: usize) -> bool {
+        let star = &self.stars[i];
+        (0.0..self.width as f32).contains(&star.x) && (0.0..self.height as f32).contains(&star.y)
+    }
+
+    /// Bring a star that drifted off one edge back in at the opposite edge,
+    /// at a fresh random position along that edge so the sky does not
+    /// repeat itself.
+    fn wrap(&mut self, i: usize) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let star = &mut self.stars[i];
+        if !(0.0..w).contains(&star.x) {
+            star.x = star.x.rem_euclid(w);
+            star.y = self.rng.f32() * h;
         }
+        if !(0.0..h).contains(&star.y) {
+            star.y = star.y.rem_euclid(h);
+            star.x = self.rng.f32() * w;
+        }
+    }
+
+    /// Put a star back near the center, as if it were still far away.
+    fn respawn_near_center(&mut self, i: usize) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let x = w / 2.0 + (self.rng.f32() - 0.5) * w * 0.3;
+        let y = h / 2.0 + (self.rng.f32() - 0.5) * h * 0.3;
+        self.stars[i].x = x;
+        self.stars[i].y = y;
     }
 
     /// Get the character and brightness for a star.
@@ -100,41 +271,145 @@ impl Starfield {
         STAR_CHARS[star.layer as usize]
     }
 
-    /// Compute a star's brightness (0.0-1.0) including twinkle.
-    pub fn star_brightness(star: &Star) -> f32 {
+    /// Compute a star's brightness (0.0-1.0) including a twinkle of
+    /// `twinkle` (±fraction of full brightness).
+    pub fn star_brightness(star: &Star, twinkle: f32) -> f32 {
         let base = match star.layer {
             0 => 0.3,
             1 => 0.6,
             _ => 0.9,
         };
-        // Gentle twinkle: ±20% variation
-        let twinkle = star.phase.sin() * 0.2;
+        let twinkle = star.phase.sin() * twinkle;
         (base + twinkle).clamp(0.0, 1.0)
     }
 
     /// Interpolate between dim and bright star colors based on brightness.
     pub fn star_color(brightness: f32, bright: Color, dim: Color) -> Color {
-        let (br, bg, bb) = color_rgb(bright);
-        let (dr, dg, db) = color_rgb(dim);
-        let t = brightness;
-        Color::Rgb(
-            lerp_u8(dr, br, t),
-            lerp_u8(dg, bg, t),
-            lerp_u8(db, bb, t),
-        )
+        lerp_color(dim, bright, brightness)
     }
 }
 
-fn color_rgb(c: Color) -> (u8, u8, u8) {
-    match c {
-        Color::Rgb(r, g, b) => (r, g, b),
-        _ => (128, 128, 128),
+impl Effect for Starfield {
+    fn z_order(&self) -> i32 {
+        -20
+    }
+
+    /// Advance the starfield animation by one tick.
+    /// `dt` is delta time in seconds.
+    fn tick(&mut self, dt: f32) {
+        if let Some(t) = self.jump {
+            let t = t + dt;
+            self.jump = (t < 2.0 * JUMP_RAMP + JUMP_HOLD).then_some(t);
+        }
+        let warp = self.warp_level();
+        let (dir_x, dir_y) = self.direction.vector();
+        for i in 0..self.stars.len() {
+            let (vx, vy) = self.warp_velocity(&self.stars[i], warp);
+            let drift =
+                self.parallax[(self.stars[i].layer as usize).min(2)] * self.speed * (1.0 - warp);
+            let star = &mut self.stars[i];
+            star.x += (vx + dir_x * drift) * dt;
+            star.y += (vy + dir_y * drift) * dt;
+            // Advance twinkle phase
+            star.phase += dt * 2.0;
+            if star.phase > std::f32::consts::TAU {
+                star.phase -= std::f32::consts::TAU;
+            }
+
+            if warp > STREAK_THRESHOLD && !self.on_screen(i) {
+                self.respawn_near_center(i);
+            } else {
+                self.wrap(i);
+            }
+        }
+    }
+
+    fn handle(&mut self, event: EffectEvent) {
+        match event {
+            EffectEvent::Warp => self.engage_warp(),
+        }
+    }
+
+    /// Re-populates if dimensions changed.
+    fn resize(&mut self, width: u16, height: u16) {
+        if width != self.width || height != self.height {
+            self.width = width;
+            self.height = height;
+            self.populate();
+        }
+    }
+
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        let warp = self.warp_level();
+        let in_area = |x: f32, y: f32| {
+            x >= area.x as f32
+                && x < (area.x + area.width) as f32
+                && y >= area.y as f32
+                && y < (area.y + area.height) as f32
+        };
+        for star in &self.stars {
+            let brightness = Self::star_brightness(star, self.twinkle);
+
+            // In warp, a streak trails back toward the center
+            if warp > STREAK_THRESHOLD {
+                let (vx, vy) = self.warp_velocity(star, warp);
+                let len = (vx.hypot(vy) * STREAK_SECONDS).min(MAX_STREAK);
+                let ch = streak_char(vx, vy);
+                let steps = len as usize;
+                for i in 1..=steps {
+                    let t = i as f32 / len;
+                    let (x, y) = (
+                        star.x - vx * STREAK_SECONDS * t,
+                        star.y - vy * STREAK_SECONDS * t,
+                    );
+                    if !in_area(x, y) {
+                        continue;
+                    }
+                    let visibility = ctx.star_visibility(x as u16, y as u16);
+                    if visibility == 0.0 {
+                        continue;
+                    }
+                    let fade = 1.0 - i as f32 / (steps + 1) as f32;
+                    let color = Self::star_color(
+                        brightness * fade * visibility,
+                        ctx.theme.star_bright,
+                        ctx.theme.star_dim,
+                    );
+                    if let Some(cell) = buf.cell_mut((x as u16, y as u16)) {
+                        cell.set_char(ch).set_fg(color);
+                    }
+                }
+            }
+
+            if in_area(star.x, star.y) {
+                let visibility = ctx.star_visibility(star.x as u16, star.y as u16);
+                if visibility == 0.0 {
+                    continue;
+                }
+                let color = Self::star_color(
+                    brightness * visibility,
+                    ctx.theme.star_bright,
+                    ctx.theme.star_dim,
+                );
+                let ch = Self::star_char(star);
+                if let Some(cell) = buf.cell_mut((star.x as u16, star.y as u16)) {
+                    cell.set_char(ch).set_fg(color);
+                }
+            }
+        }
     }
 }
 
-fn lerp_u8(a: u8, b: u8, t: f32) -> u8 {
-    let v = a as f32 + (b as f32 - a as f32) * t;
-    v.clamp(0.0, 255.0) as u8
+/// Line character closest to the on-screen direction of `(vx, vy)`.
+pub(super) fn streak_char(vx: f32, vy: f32) -> char {
+    // Rows are about twice as tall as columns are wide
+    let angle = (vy * 2.0).atan2(vx).to_degrees().rem_euclid(180.0);
+    match angle {
+        a if !(22.5..157.5).contains(&a) => '─',
+        a if (67.5..112.5).contains(&a) => '│',
+        a if a < 90.0 => '╲',
+        _ => '╱',
+    }
 }
 
 #[cfg(test)]
@@ -167,8 +442,10 @@ mod tests {
             layer: 2,
             phase: 0.0,
         };
-        let b = Starfield::star_brightness(&star);
+        let b = Starfield::star_brightness(&star, DEFAULT_TWINKLE);
         assert!((0.0..=1.0).contains(&b));
+        let steady = Starfield::star_brightness(&Star { phase: 1.2, ..star }, 0.0);
+        assert_eq!(steady, 0.9);
     }
 
     #[test]
@@ -183,11 +460,121 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_custom_parallax() {
+        let mut sf = Starfield::new(80, 24, 50, Some(42)).with_parallax([0.0, 0.0, 0.0]);
+        let before: Vec<f32> = sf.stars.iter().map(|s| s.x).collect();
+        sf.tick(1.0);
+        let after: Vec<f32> = sf.stars.iter().map(|s| s.x).collect();
+        assert_eq!(before, after, "zero parallax should hold stars still");
+    }
+
+    #[test]
+    fn test_warp_jump_envelope() {
+        let mut sf = Starfield::new(80, 24, 50, Some(42));
+        assert_eq!(sf.warp_level(), 0.0);
+        sf.engage_warp();
+        sf.tick(JUMP_RAMP + 0.5);
+        assert_eq!(sf.warp_level(), 1.0);
+        for _ in 0..100 {
+            sf.tick(0.1);
+        }
+        assert_eq!(sf.warp_level(), 0.0, "jump should wear off");
+        assert!(sf.jump.is_none());
+
+        let cruising = Starfield::new(80, 24, 50, Some(42)).with_mode(StarMode::Warp);
+        assert_eq!(cruising.warp_level(), WARP_CRUISE);
+    }
+
+    #[test]
+    fn test_warp_moves_stars_outward() {
+        let mut sf = Starfield::new(80, 24, 0, Some(1)).with_mode(StarMode::Warp);
+        sf.stars.push(Star {
+            x: 60.0,
+            y: 18.0,
+            layer: 2,
+            phase: 0.0,
+        });
+        sf.tick(0.1);
+        assert!(sf.stars[0].x > 60.0 && sf.stars[0].y > 18.0);
+    }
+
+    #[test]
+    fn test_warp_keeps_stars_on_screen() {
+        let mut sf = Starfield::new(80, 24, 60, Some(7)).with_mode(StarMode::Warp);
+        sf.engage_warp();
+        for _ in 0..200 {
+            sf.tick(0.05);
+            for star in &sf.stars {
+                assert!((0.0..80.0).contains(&star.x) && (0.0..24.0).contains(&star.y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_streak_char() {
+        assert_eq!(streak_char(1.0, 0.0), '─');
+        assert_eq!(streak_char(-1.0, 0.0), '─');
+        assert_eq!(streak_char(0.0, 1.0), '│');
+        assert_eq!(streak_char(1.0, 0.5), '╲');
+        assert_eq!(streak_char(-1.0, 0.5), '╱');
+    }
+
+    #[test]
+    fn test_parse_direction() {
+        assert_eq!(StarDirection::parse("up"), Ok(StarDirection::Angle(90.0)));
+        assert_eq!(StarDirection::parse("-45"), Ok(StarDirection::Angle(315.0)));
+        assert_eq!(StarDirection::parse("Still"), Ok(StarDirection::Still));
+        assert!(StarDirection::parse("sideways").is_err());
+        assert!(StarDirection::parse("NaN").is_err());
+    }
+
+    #[test]
+    fn test_drift_direction() {
+        let star = Star {
+            x: 40.0,
+            y: 12.0,
+            layer: 2,
+            phase: 0.0,
+        };
+        let moved = |direction| {
+            let mut sf = Starfield::new(80, 24, 0, Some(1)).with_drift(direction, 2.0);
+            sf.stars.push(star.clone());
+            sf.tick(1.0);
+            (sf.stars[0].x - 40.0, sf.stars[0].y - 12.0)
+        };
+        let (dx, dy) = moved(StarDirection::Angle(90.0));
+        assert!(
+            dx.abs() < 1e-4 && (dy + 1.0).abs() < 1e-4,
+            "up at 2x: {dx},{dy}"
+        );
+        let (dx, dy) = moved(StarDirection::Angle(0.0));
+        assert!((dx - 2.0).abs() < 1e-4 && dy.abs() < 1e-4);
+        assert_eq!(moved(StarDirection::Still), (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_wraps_on_every_edge() {
+        for angle in [0.0, 90.0, 180.0, 270.0, 45.0, 225.0] {
+            let mut sf =
+                Starfield::new(40, 12, 60, Some(3)).with_drift(StarDirection::Angle(angle), 20.0);
+            for _ in 0..200 {
+                sf.tick(0.1);
+                for star in &sf.stars {
+                    assert!(
+                        (0.0..40.0).contains(&star.x) && (0.0..12.0).contains(&star.y),
+                        "star escaped at {angle}°: {star:?}"
+                    );
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_resize_repopulates() {
         let mut sf = Starfield::new(80, 24, 50, Some(42));
         let old_count = sf.stars.len();
-        sf.resize(160, 48, 50);
+        sf.resize(160, 48);
         // Larger area should produce more stars
         assert!(sf.stars.len() > old_count);
     }
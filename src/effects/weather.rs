@@ -0,0 +1,266 @@
+//! Weather in front of the clock: falling snow, slanting rain and drifting
+//! cosmic dust, all settling on top of the digits.
+
+use std::f32::consts::TAU;
+
+use fastrand::Rng;
+use ratatui::{buffer::Buffer, layout::Rect};
+
+use super::particles::{Landing, Obstacles, Particle, ParticleSystem};
+use super::starfield::streak_char;
+use super::{Effect, RenderContext};
+
+/// Which weather to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherKind {
+    /// Flakes swaying down and piling on the digits for a while
+    Snow,
+    /// Fast drops slanting down to the left, splashing on the digits
+    Rain,
+    /// Faint motes drifting across the sky and slowly settling
+    Dust,
+}
+
+/// Rain falls this many columns sideways per row.
+const RAIN_SLANT: f32 = 0.9;
+
+/// Seconds simulated up front, so the sky is not empty at start.
+const PREWARM_SECONDS: f32 = 10.0;
+
+const SNOW_GLYPHS: [char; 3] = ['·', '•', '*'];
+const DUST_GLYPHS: [char; 2] = ['·', '∙'];
+
+/// The weather effect.
+#[derive(Debug)]
+pub struct Weather {
+    kind: WeatherKind,
+    width: u16,
+    height: u16,
+    rng: Rng,
+    system: ParticleSystem,
+    /// Fractional particles owed to the spawn rate
+    budget: f32,
+    /// Digit strokes from the last drawn frame
+    obstacles: Obstacles,
+}
+
+impl Weather {
+    pub fn new(kind: WeatherKind, width: u16, height: u16, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(s) => Rng::with_seed(s.wrapping_add(0x5707)),
+            None => Rng::new(),
+        };
+        let landing = match kind {
+            WeatherKind::Snow => Landing {
+                glyph: '▁',
+                seconds: (6.0, 12.0),
+            },
+            WeatherKind::Rain => Landing {
+                glyph: '·',
+                seconds: (0.1, 0.25),
+            },
+            WeatherKind::Dust => Landing {
+                glyph: '.',
+                seconds: (3.0, 6.0),
+            },
+        };
+        let mut weather = Self {
+            kind,
+            width,
+            height,
+            rng,
+            system: ParticleSystem::new(landing),
+            budget: 0.0,
+            obstacles: Obstacles::default(),
+        };
+        let steps = (PREWARM_SECONDS * 10.0) as usize;
+        for _ in 0..steps {
+            weather.tick(0.1);
+        }
+        weather
+    }
+
+    /// New particles per second for the current size.
+    fn spawn_rate(&self) -> f32 {
+        let (w, h) = (self.width as f32, self.height as f32);
+        match self.kind {
+            WeatherKind::Snow => w * 0.12,
+            WeatherKind::Rain => w * 0.9,
+            WeatherKind::Dust => w * h / 400.0,
+        }
+    }
+
+    fn spawn(&mut self) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        let rng = &mut self.rng;
+        let particle = match self.kind {
+            WeatherKind::Snow => {
+                let glyph = SNOW_GLYPHS[rng.usize(..SNOW_GLYPHS.len())];
+                let x = rng.f32() * w;
+                let (vx, vy) = (rng.f32() - 0.5, 2.0 + rng.f32() * 2.0);
+                Particle::new(x, 0.0, vx, vy, f32::INFINITY, glyph)
+                    .with_brightness(0.6 + rng.f32() * 0.4)
+                    .with_fade(0.0, 1.5)
+                    .with_sway(0.8, rng.f32() * TAU)
+            }
+            WeatherKind::Rain => {
+                let vy = 18.0 + rng.f32() * 10.0;
+                let vx = -vy * RAIN_SLANT;
+                // Start far enough right to cover the screen as drops drift left
+                let x = rng.f32() * (w + h * RAIN_SLANT);
+                Particle::new(x, 0.0, vx, vy, f32::INFINITY, streak_char(vx, vy))
+                    .with_brightness(0.4 + rng.f32() * 0.4)
+                    .with_fade(0.0, 0.1)
+            }
+            WeatherKind::Dust => {
+                let glyph = DUST_GLYPHS[rng.usize(..DUST_GLYPHS.len())];
+                let (x, y) = (rng.f32() * w, rng.f32() * h);
+                let vx = 0.3 + rng.f32() * 0.8;
+                let vy = 0.05 + rng.f32() * 0.2;
+                let life = 6.0 + rng.f32() * 8.0;
+                Particle::new(x, y, vx, vy, life, glyph)
+                    .with_brightness(0.5 + rng.f32() * 0.5)
+                    .with_fade(1.5, 1.5)
+                    .with_sway(0.3, rng.f32() * TAU)
+            }
+        };
+        self.system.spawn(particle);
+    }
+}
+
+impl Effect for Weather {
+    /// In front of the digits, where it can see them to settle on, and
+    /// after the glow so particles keep its halo behind them.
+    fn z_order(&self) -> i32 {
+        6
+    }
+
+    fn tick(&mut self, dt: f32) {
+        self.budget += self.spawn_rate() * dt;
+        while self.budget >= 1.0 {
+            self.budget -= 1.0;
+            self.spawn();
+        }
+        self.system
+            .update(dt, self.width, self.height, &self.obstacles, &mut self.rng);
+    }
+
+    fn resize(&mut self, width: u16, height: u16) {
+        self.width = width;
+        self.height = height;
+        self.obstacles = Obstacles::default();
+    }
+
+    fn render(&mut self, buf: &mut Buffer, area: Rect, ctx: &RenderContext) {
+        self.obstacles = Obstacles::from_digits(&ctx.digits, area);
+        let theme = ctx.theme;
+        let color = match self.kind {
+            WeatherKind::Snow => theme.star_bright,
+            WeatherKind::Rain => theme.secondary,
+            WeatherKind::Dust => theme.star_dim,
+        };
+        self.system.render(buf, area, theme.bg, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::ColorMode;
+    use crate::effects::{ClearZone, DigitMask};
+    use crate::theme::Theme;
+
+    const KINDS: [WeatherKind; 3] = [WeatherKind::Snow, WeatherKind::Rain, WeatherKind::Dust];
+
+    #[test]
+    fn test_prewarmed_and_deterministic() {
+        for kind in KINDS {
+            let a = Weather::new(kind, 80, 24, Some(3));
+            let b = Weather::new(kind, 80, 24, Some(3));
+            assert!(!a.system.particles.is_empty(), "{kind:?}");
+            assert_eq!(a.system.particles, b.system.particles, "{kind:?}");
+        }
+    }
+
+    #[test]
+    fn test_snow_settles_on_digits() {
+        let theme = Theme::void();
+        let area = Rect::new(0, 0, 40, 12);
+        let ctx = RenderContext {
+            theme: &theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::from_lines(Rect::new(0, 6, 40, 1), &["█".repeat(40)]),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        };
+        let mut snow = Weather::new(WeatherKind::Snow, 40, 12, Some(1));
+        let frame = || {
+            let mut buf = Buffer::empty(area);
+            for x in 0..40 {
+                buf[(x, 6)].set_char('█');
+            }
+            buf
+        };
+        for _ in 0..200 {
+            snow.render(&mut frame(), area, &ctx);
+            snow.tick(1.0 / 30.0);
+        }
+        let mut buf = frame();
+        snow.render(&mut buf, area, &ctx);
+        let row = |y: u16| (0..40).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert!(row(5).contains('▁'), "snow should pile on the stroke");
+        assert!(row(6).chars().all(|c| c == '█'), "digits stay in front");
+        assert!(
+            (7..12).all(|y| row(y).trim().is_empty()),
+            "nothing falls through the stroke"
+        );
+    }
+
+    #[test]
+    fn test_snow_falls_past_other_blocks() {
+        // A row of half blocks the font did not draw, like a planet's edge
+        let theme = Theme::void();
+        let area = Rect::new(0, 0, 40, 12);
+        let ctx = RenderContext {
+            theme: &theme,
+            transparent: false,
+            color_mode: ColorMode::Truecolor,
+            elapsed: 0.0,
+            digits: DigitMask::from_lines(Rect::new(0, 6, 40, 1), &[" ".repeat(40)]),
+            clear_zone: Rect::default(),
+            clear_mode: ClearZone::Off,
+        };
+        let mut snow = Weather::new(WeatherKind::Snow, 40, 12, Some(1));
+        let frame = || {
+            let mut buf = Buffer::empty(area);
+            for x in 0..40 {
+                buf[(x, 6)].set_char('▀');
+            }
+            buf
+        };
+        for _ in 0..200 {
+            snow.render(&mut frame(), area, &ctx);
+            snow.tick(1.0 / 30.0);
+        }
+        let mut buf = frame();
+        snow.render(&mut buf, area, &ctx);
+        let row = |y: u16| (0..40).map(|x| buf[(x, y)].symbol()).collect::<String>();
+        assert!(!row(5).contains('▁'), "nothing to settle on");
+        assert!(
+            (7..12).any(|y| !row(y).trim().is_empty()),
+            "snow falls on past the blocks"
+        );
+    }
+
+    #[test]
+    fn test_rain_slants_left() {
+        let rain = Weather::new(WeatherKind::Rain, 80, 24, Some(2));
+        assert!(rain
+            .system
+            .particles
+            .iter()
+            .all(|p| p.vx < 0.0 && p.vy > 0.0 && p.glyph == '╱'));
+    }
+}
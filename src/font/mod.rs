@@ -1,7 +1,7 @@
-/// Big-digit font for the clock display.
-///
-/// Each glyph is 5 lines tall and a fixed width (varies per character).
-/// Uses Unicode block characters for a clean 7-segment-like look.
+//! Big-digit font for the clock display.
+//!
+//! Each glyph is 5 lines tall and a fixed width (varies per character).
+//! Uses Unicode block characters for a clean 7-segment-like look.
 
 /// Height of each glyph in rows.
 pub const GLYPH_HEIGHT: usize = 5;
@@ -0,0 +1,197 @@
+//! Derive a complete theme from a single seed color.
+//!
+//! Works in OKLCH (perceptual lightness, chroma, hue) so that every derived
+//! color keeps the seed's hue while lightness steps look even across hues.
+
+use ratatui::style::Color;
+
+use crate::theme::{color_rgb, Theme};
+
+/// A color in OKLCH: lightness 0-1, chroma (roughly 0-0.37), hue in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Oklch {
+    l: f32,
+    c: f32,
+    h: f32,
+}
+
+impl Oklch {
+    fn from_rgb((r, g, b): (u8, u8, u8)) -> Self {
+        let lin = |v: u8| {
+            let v = v as f32 / 255.0;
+            if v <= 0.04045 {
+                v / 12.92
+            } else {
+                ((v + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (lin(r), lin(g), lin(b));
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+        let lab_l = 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s;
+        let lab_a = 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s;
+        let lab_b = 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s;
+        Self {
+            l: lab_l,
+            c: lab_a.hypot(lab_b),
+            h: lab_b.atan2(lab_a).to_degrees().rem_euclid(360.0),
+        }
+    }
+
+    /// Linear sRGB components; may fall outside 0-1 when out of gamut.
+    fn to_linear(self) -> (f32, f32, f32) {
+        let (a, b) = (
+            self.c * self.h.to_radians().cos(),
+            self.c * self.h.to_radians().sin(),
+        );
+        let l = (self.l + 0.396_337_78 * a + 0.215_803_76 * b).powi(3);
+        let m = (self.l - 0.105_561_346 * a - 0.063_854_17 * b).powi(3);
+        let s = (self.l - 0.089_484_18 * a - 1.291_485_5 * b).powi(3);
+        (
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        )
+    }
+
+    fn in_gamut(self) -> bool {
+        let (r, g, b) = self.to_linear();
+        [r, g, b].iter().all(|v| (-1e-4..=1.0 + 1e-4).contains(v))
+    }
+
+    /// Convert to sRGB, reducing chroma until the color is displayable.
+    fn to_color(self) -> Color {
+        let mut lo = 0.0;
+        let mut hi = self.c;
+        let mut color = self;
+        if !color.in_gamut() {
+            for _ in 0..20 {
+                let mid = (lo + hi) / 2.0;
+                color.c = mid;
+                if color.in_gamut() {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            color.c = lo;
+        }
+        let (r, g, b) = color.to_linear();
+        let encode = |v: f32| {
+            let v = v.clamp(0.0, 1.0);
+            let v = if v <= 0.003_130_8 {
+                v * 12.92
+            } else {
+                1.055 * v.powf(1.0 / 2.4) - 0.055
+            };
+            (v * 255.0).round() as u8
+        };
+        Color::Rgb(encode(r), encode(g), encode(b))
+    }
+
+    fn with(self, l: f32, c: f32) -> Self {
+        Self { l, c, ..self }
+    }
+}
+
+/// Build a harmonious theme around `seed`, used as the accent color.
+///
+/// Everything else keeps the seed's hue: a deeper secondary, a muted date
+/// line, near-white and faint stars, a lighter shooting star nudged toward a
+/// neighbouring hue, an aurora glow nudged the other way, and a barely
+/// tinted near-black background.
+pub fn theme_from_seed(name: &str, seed: Color) -> Theme {
+    let base = Oklch::from_rgb(color_rgb(seed).unwrap_or((0, 255, 255)));
+    let chroma = base.c;
+    let shooting = Oklch {
+        h: (base.h + 20.0).rem_euclid(360.0),
+        ..base
+    };
+
+    let mut theme = Theme::void();
+    theme.name = name.to_string();
+    theme.accent = seed;
+    theme.secondary = base.with(base.l * 0.72, chroma * 0.8).to_color();
+    theme.date_color = base.with(0.55, chroma * 0.35).to_color();
+    theme.star_bright = base.with(0.94, 0.03).to_color();
+    theme.star_dim = base.with(0.42, 0.035).to_color();
+    theme.shooting_star = shooting
+        .with((base.l + 0.12).min(0.92), chroma * 0.6)
+        .to_color();
+    theme.scanline = base.with(0.2, 0.04).to_color();
+    theme.aurora = Oklch {
+        h: (base.h - 30.0).rem_euclid(360.0),
+        ..base
+    }
+    .with(0.72, chroma.max(0.08))
+    .to_color();
+    theme.bg = base.with(0.09, 0.015).to_color();
+    theme.gradient = None;
+    theme
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lightness(c: Color) -> f32 {
+        Oklch::from_rgb(color_rgb(c).unwrap()).l
+    }
+
+    #[test]
+    fn test_oklch_round_trip() {
+        for rgb in [
+            (255, 106, 193),
+            (0, 255, 255),
+            (12, 34, 56),
+            (255, 255, 255),
+        ] {
+            let back = Oklch::from_rgb(rgb).to_color();
+            let Color::Rgb(r, g, b) = back else {
+                panic!("expected rgb");
+            };
+            let diff = |a: u8, b: u8| (a as i32 - b as i32).abs();
+            assert!(
+                diff(r, rgb.0) <= 1 && diff(g, rgb.1) <= 1 && diff(b, rgb.2) <= 1,
+                "{rgb:?} -> {back:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_seed_is_accent() {
+        let seed = Color::Rgb(255, 106, 193);
+        let t = theme_from_seed("pink", seed);
+        assert_eq!(t.name, "pink");
+        assert_eq!(t.accent, seed);
+    }
+
+    #[test]
+    fn test_derived_lightness_order() {
+        let t = theme_from_seed("x", Color::Rgb(80, 200, 120));
+        assert!(lightness(t.bg) < lightness(t.scanline));
+        assert!(lightness(t.scanline) < lightness(t.star_dim));
+        assert!(lightness(t.star_dim) < lightness(t.star_bright));
+        assert!(lightness(t.secondary) < lightness(t.accent));
+    }
+
+    #[test]
+    fn test_bg_is_near_black() {
+        let t = theme_from_seed("x", Color::Rgb(255, 200, 50));
+        let (r, g, b) = color_rgb(t.bg).unwrap();
+        assert!(r < 24 && g < 24 && b < 24, "bg = {:?}", t.bg);
+    }
+
+    #[test]
+    fn test_out_of_gamut_is_clipped() {
+        let c = Oklch {
+            l: 0.7,
+            c: 0.4,
+            h: 150.0,
+        };
+        assert!(!c.in_gamut());
+        assert!(matches!(c.to_color(), Color::Rgb(..)));
+    }
+}
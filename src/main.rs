@@ -1,15 +1,22 @@
 mod app;
+mod astro;
 mod cli;
+mod color;
+mod commands;
+mod config;
 mod effects;
 mod font;
+mod generate;
+mod named_colors;
 mod render;
+mod schedule;
 mod theme;
 mod util;
 
+use std::ffi::OsString;
 use std::io;
 use std::time::{Duration, Instant};
 
-use clap::Parser;
 use color_eyre::eyre::Result;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
@@ -19,13 +26,28 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use app::App;
-use cli::Args;
+use config::{LoadError, Reloader};
+use effects::EffectEvent;
 
 fn main() -> Result<()> {
     // Install panic hook that restores terminal before printing backtrace.
     color_eyre::install()?;
 
-    let args = Args::parse();
+    let argv: Vec<OsString> = std::env::args_os().collect();
+    let args = match config::load_args(&argv) {
+        Ok(args) => args,
+        // Command-line mistakes, --help and --version, reported by clap
+        Err(LoadError::Cli(e)) => e.exit(),
+        Err(LoadError::Config(e)) => {
+            eprintln!("stardial: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    // Subcommands print to stdout and exit without touching the terminal.
+    if let Some(ref command) = args.command {
+        return commands::run(command, &args);
+    }
 
     // Optional file logging (keep guard alive for duration of program).
     let _log_guard = if let Some(ref log_path) = args.log {
@@ -36,6 +58,12 @@ fn main() -> Result<()> {
 
     tracing::info!("stardial starting with args: {:?}", args);
 
+    if let Err(e) = app::check_themes(&args) {
+        eprintln!("stardial: {e}");
+        std::process::exit(2);
+    }
+    let mut reloader = Reloader::new(argv, &args)?;
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -56,8 +84,9 @@ fn main() -> Result<()> {
     let size = terminal.size()?;
     let mut app = App::new(&args, size.width, size.height);
     tracing::info!("theme: {}, size: {}x{}", app.theme.name, size.width, size.height);
+    tracing::info!("color mode: {:?}", app.color_mode);
 
-    let result = run_loop(&mut terminal, &mut app);
+    let result = run_loop(&mut terminal, &mut app, &mut reloader);
 
     // Restore terminal (always, even on error)
     disable_raw_mode()?;
@@ -67,8 +96,11 @@ fn main() -> Result<()> {
     result
 }
 
-fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App) -> Result<()> {
-    let frame_duration = Duration::from_secs_f64(1.0 / app.fps as f64);
+fn run_loop(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+    reloader: &mut Reloader,
+) -> Result<()> {
     let mut last_frame = Instant::now();
 
     loop {
@@ -76,6 +108,17 @@ fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App
             return Ok(());
         }
 
+        // Pick up edits to the config and theme files, or a SIGHUP
+        match reloader.poll() {
+            Some(Ok(args)) => match app.reload(&args) {
+                Ok(()) => tracing::info!("reloaded settings, theme: {}", app.base_theme.name),
+                Err(e) => tracing::warn!("reload failed, keeping current settings: {e}"),
+            },
+            Some(Err(e)) => tracing::warn!("reload failed, keeping current settings: {e}"),
+            None => {}
+        }
+        let frame_duration = Duration::from_secs_f64(1.0 / app.fps as f64);
+
         // Compute dt
         let now = Instant::now();
         let dt = now.duration_since(last_frame).as_secs_f32();
@@ -102,6 +145,12 @@ fn run_loop(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App
                     {
                         app.should_quit = true;
                     }
+                    KeyCode::Char('t') => {
+                        app.cycle_theme();
+                    }
+                    KeyCode::Char('w') => {
+                        app.send_effect_event(EffectEvent::Warp);
+                    }
                     _ => {}
                 },
                 Event::Resize(w, h) => {
@@ -2,49 +2,44 @@ use chrono::Local;
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     widgets::Widget,
     Frame,
 };
 
 use crate::app::App;
-use crate::effects::scanline;
-use crate::effects::shooting_star::ShootingStarManager;
-use crate::effects::starfield::Starfield;
+use crate::color::{self, ColorMode};
+use crate::effects::{DigitMask, RenderContext};
 use crate::font;
 
 /// Render the full frame.
-pub fn draw(frame: &mut Frame, app: &App) {
+pub fn draw(frame: &mut Frame, app: &mut App) {
     let area = frame.area();
     let clock_widget = ClockWidget { app };
     frame.render_widget(clock_widget, area);
 }
 
 struct ClockWidget<'a> {
-    app: &'a App,
+    app: &'a mut App,
 }
 
 impl<'a> Widget for ClockWidget<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let app = self.app;
 
-        // Clear background
-        for y in area.y..area.y + area.height {
-            for x in area.x..area.x + area.width {
-                if let Some(cell) = buf.cell_mut((x, y)) {
-                    cell.set_char(' ');
-                    cell.set_style(Style::default().bg(app.theme.bg));
+        // Clear background (a transparent background is left to the terminal)
+        if !app.transparent {
+            for y in area.y..area.y + area.height {
+                for x in area.x..area.x + area.width {
+                    if let Some(cell) = buf.cell_mut((x, y)) {
+                        cell.set_char(' ');
+                        cell.set_style(Style::default().bg(app.theme.bg));
+                    }
                 }
             }
         }
 
-        // Render starfield
-        if app.effects_enabled {
-            render_starfield(buf, area, app);
-            render_shooting_stars(buf, area, app);
-        }
-
-        // Render big clock digits
+        // Lay out the big clock digits
         let now = Local::now();
         let time_str = now.format(&app.time_format).to_string();
 
@@ -57,6 +52,40 @@ impl<'a> Widget for ClockWidget<'a> {
         // Center the clock, slightly above center
         let cx = area.x + area.width.saturating_sub(text_width) / 2;
         let cy = area.y + area.height.saturating_sub(text_height + 2) / 2;
+        let clock = Rect::new(cx, cy, text_width, text_height);
+
+        // Date line below the clock
+        let date_str = now.format("%Y-%m-%d %A").to_string();
+        let date_x = area.x + area.width.saturating_sub(date_str.len() as u16) / 2;
+        let date_y = cy + text_height + 1;
+        let date = Rect::new(date_x, date_y, date_str.len() as u16, 1);
+        let bounds = if app.show_date {
+            clock.union(date)
+        } else {
+            clock
+        };
+
+        let ctx = RenderContext {
+            theme: &app.theme,
+            transparent: app.transparent,
+            color_mode: app.color_mode,
+            elapsed: app.elapsed,
+            digits: DigitMask::from_lines(clock, &lines),
+            clear_zone: clear_zone(bounds, app.clear_margin, area),
+            clear_mode: app.clear_zone,
+        };
+        let effects: &mut [_] = if app.effects_enabled {
+            &mut app.effects
+        } else {
+            &mut []
+        };
+        let (behind, in_front): (Vec<_>, Vec<_>) =
+            effects.iter_mut().partition(|e| e.z_order() < 0);
+
+        // Background effects (stars, ...)
+        for effect in behind {
+            effect.render(buf, area, &ctx);
+        }
 
         // Draw digit glyphs
         for (row, line) in lines.iter().enumerate() {
@@ -71,43 +100,58 @@ impl<'a> Widget for ClockWidget<'a> {
                 }
                 if ch != ' ' {
                     if let Some(cell) = buf.cell_mut((x, y)) {
-                        cell.set_char(ch);
-                        let color = glyph_color(ch, &app.theme);
-                        cell.set_style(Style::default().fg(color).bg(app.theme.bg));
+                        let u = col as f32 / text_width.saturating_sub(1).max(1) as f32;
+                        let v = row as f32 / text_height.saturating_sub(1).max(1) as f32;
+                        let color = glyph_color(ch, &app.theme, u, v, app.elapsed);
+                        cell.set_char(ch).set_fg(color);
                     }
                 }
             }
         }
 
         // Render date line below clock
-        if app.show_date {
-            let date_str = now.format("%Y-%m-%d %A").to_string();
-            let date_x = area.x + area.width.saturating_sub(date_str.len() as u16) / 2;
-            let date_y = cy + text_height + 1;
-            if date_y < area.y + area.height {
-                for (i, ch) in date_str.chars().enumerate() {
-                    let x = date_x + i as u16;
-                    if x < area.x + area.width {
-                        if let Some(cell) = buf.cell_mut((x, date_y)) {
-                            cell.set_char(ch);
-                            cell.set_style(
-                                Style::default().fg(app.theme.date_color).bg(app.theme.bg),
-                            );
-                        }
+        if app.show_date && date_y < area.y + area.height {
+            for (i, ch) in date_str.chars().enumerate() {
+                let x = date_x + i as u16;
+                if x < area.x + area.width {
+                    if let Some(cell) = buf.cell_mut((x, date_y)) {
+                        cell.set_char(ch).set_fg(app.theme.date_color);
                     }
                 }
             }
         }
 
-        // Scanline overlay (last pass)
-        if app.effects_enabled {
-            render_scanlines(buf, area, app);
+        // Overlay effects (glow, scanlines, ...)
+        for effect in in_front {
+            effect.render(buf, area, &ctx);
+        }
+
+        // Map everything onto the terminal's palette (last pass)
+        if app.color_mode != ColorMode::Truecolor {
+            quantize_colors(buf, area, app.color_mode);
         }
     }
 }
 
-/// Map glyph characters to appropriate colors.
-fn glyph_color(ch: char, theme: &crate::theme::Theme) -> Color {
+/// The clock's bounding box grown by `margin` columns on either side and
+/// half as many rows (rows are about twice as tall), within `area`.
+fn clear_zone(bounds: Rect, margin: u16, area: Rect) -> Rect {
+    let rows = margin.div_ceil(2);
+    Rect::new(
+        bounds.x.saturating_sub(margin),
+        bounds.y.saturating_sub(rows),
+        bounds.width + 2 * margin,
+        bounds.height + 2 * rows,
+    )
+    .intersection(area)
+}
+
+/// Map glyph characters to appropriate colors. With a gradient, every
+/// glyph cell takes its color from its position `(u, v)` on the clock face.
+fn glyph_color(ch: char, theme: &crate::theme::Theme, u: f32, v: f32, elapsed: f32) -> Color {
+    if let Some(ref gradient) = theme.gradient {
+        return gradient.color_at(u, v, elapsed);
+    }
     match ch {
         '█' => theme.accent,
         '▀' | '▄' => theme.secondary,
@@ -115,83 +159,43 @@ fn glyph_color(ch: char, theme: &crate::theme::Theme) -> Color {
     }
 }
 
-fn render_starfield(buf: &mut Buffer, area: Rect, app: &App) {
-    for star in &app.starfield.stars {
-        let x = star.x as u16;
-        let y = star.y as u16;
-        if x >= area.x && x < area.x + area.width && y >= area.y && y < area.y + area.height {
-            let brightness = Starfield::star_brightness(star);
-            let color =
-                Starfield::star_color(brightness, app.theme.star_bright, app.theme.star_dim);
-            let ch = Starfield::star_char(star);
-            if let Some(cell) = buf.cell_mut((x, y)) {
-                cell.set_char(ch);
-                cell.set_style(Style::default().fg(color).bg(app.theme.bg));
-            }
-        }
-    }
-}
-
-fn render_shooting_stars(buf: &mut Buffer, area: Rect, app: &App) {
-    for star in &app.shooting_stars.active {
-        let positions = ShootingStarManager::trail_positions(star);
-        for (i, (x, y, ch)) in positions.iter().enumerate() {
-            if *x >= area.x
-                && *x < area.x + area.width
-                && *y >= area.y
-                && *y < area.y + area.height
-            {
-                // Fade trail: head is bright, tail is dim
-                let fade = 1.0 - (i as f32 / positions.len() as f32);
-                let (r, g, b) = match app.theme.shooting_star {
-                    Color::Rgb(r, g, b) => (r, g, b),
-                    _ => (200, 220, 255),
-                };
-                let color = Color::Rgb(
-                    (r as f32 * fade) as u8,
-                    (g as f32 * fade) as u8,
-                    (b as f32 * fade) as u8,
-                );
-                if let Some(cell) = buf.cell_mut((*x, *y)) {
-                    cell.set_char(*ch);
-                    cell.set_style(Style::default().fg(color).bg(app.theme.bg));
-                }
-            }
-        }
-    }
-}
-
-fn render_scanlines(buf: &mut Buffer, area: Rect, app: &App) {
+/// Reduce every cell's colors to the palette of `mode`. In 16-color mode,
+/// colors much darker than their palette match are drawn dim instead; in
+/// mono mode all color is dropped (including background fills) and
+/// brightness is conveyed with bold/dim and lighter glyphs for dim stars.
+fn quantize_colors(buf: &mut Buffer, area: Rect, mode: ColorMode) {
     for y in area.y..area.y + area.height {
-        let opacity = scanline::scanline_opacity(y, app.elapsed);
-        if opacity > 0.01 {
-            let (sr, sg, sb) = match app.theme.scanline {
-                Color::Rgb(r, g, b) => (r, g, b),
-                _ => (0, 0, 0),
-            };
-            for x in area.x..area.x + area.width {
-                if let Some(cell) = buf.cell_mut((x, y)) {
-                    // Darken the existing bg slightly toward scanline color
-                    if let Color::Rgb(br, bg_c, bb) = cell.bg {
-                        let nr = lerp(br, sr, opacity);
-                        let ng = lerp(bg_c, sg, opacity);
-                        let nb = lerp(bb, sb, opacity);
-                        cell.set_style(cell.style().bg(Color::Rgb(nr, ng, nb)));
+        for x in area.x..area.x + area.width {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                match mode {
+                    ColorMode::Ansi16 if color::needs_dim(cell.fg) => {
+                        cell.modifier.insert(Modifier::DIM);
                     }
+                    ColorMode::Mono if cell.symbol() != " " => {
+                        cell.modifier.insert(color::mono_modifier(cell.fg));
+                        if let Some(ch) = color::mono_glyph(cell.symbol(), cell.fg) {
+                            cell.set_char(ch);
+                        }
+                    }
+                    _ => {}
                 }
+                cell.fg = color::quantize(cell.fg, mode);
+                cell.bg = color::quantize(cell.bg, mode);
             }
         }
     }
 }
 
-fn lerp(a: u8, b: u8, t: f32) -> u8 {
-    let v = a as f32 + (b as f32 - a as f32) * t;
-    v.clamp(0.0, 255.0) as u8
+/// Render one frame off-screen into a `width` x `height` buffer.
+pub fn render_to_buffer(app: &mut App, width: u16, height: u16) -> Buffer {
+    let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+    ClockWidget { app }.render(buf.area, &mut buf);
+    buf
 }
 
 /// Render to a string buffer for headless/snapshot testing.
 #[cfg(test)]
-pub fn render_to_string(app: &App, width: u16, height: u16) -> String {
+pub fn render_to_string(app: &mut App, width: u16, height: u16) -> String {
     use ratatui::backend::TestBackend;
     use ratatui::Terminal;
 
@@ -217,33 +221,30 @@ pub fn render_to_string(app: &App, width: u16, height: u16) -> String {
 
 #[cfg(test)]
 mod tests {
+    use clap::Parser;
+
     use super::*;
     use crate::cli::Args;
+    use crate::effects::crt::CrtPass;
+    use crate::effects::{ClearZone, EffectEvent, EffectKind};
+    use crate::theme::{GradientMode, Theme};
 
     fn test_args() -> Args {
-        Args {
-            format: "%H:%M:%S".into(),
-            twelve: false,
-            twentyfour: false,
-            date: false,
-            no_seconds: false,
-            blink: false,
-            fps: 30,
-            theme: "void".into(),
-            color: None,
-            no_effects: true,
-            size: 1,
-            stars: 0,
-            seed: Some(42),
-            log: None,
-        }
+        Args::parse_from([
+            "stardial",
+            "--color-mode=truecolor",
+            "--no-effects",
+            "--crt=vignette",
+            "--stars=0",
+            "--seed=42",
+        ])
     }
 
     #[test]
     fn test_headless_render_contains_digits() {
         let args = test_args();
-        let app = App::new(&args, 80, 24);
-        let output = render_to_string(&app, 80, 24);
+        let mut app = App::new(&args, 80, 24);
+        let output = render_to_string(&mut app, 80, 24);
         // Should contain block characters (digits are rendered)
         assert!(
             output.contains('█'),
@@ -255,25 +256,229 @@ mod tests {
     fn test_headless_render_with_date() {
         let mut args = test_args();
         args.date = true;
-        let app = App::new(&args, 80, 24);
-        let output = render_to_string(&app, 80, 24);
+        let mut app = App::new(&args, 80, 24);
+        let output = render_to_string(&mut app, 80, 24);
         assert!(output.contains('█'));
     }
 
+    #[test]
+    fn test_glyph_color_gradient() {
+        let theme = Theme::void().with_gradient_mode(GradientMode::Horizontal);
+        assert_eq!(glyph_color('█', &theme, 0.0, 0.0, 0.0), theme.accent);
+        assert_eq!(glyph_color('▀', &theme, 1.0, 0.0, 0.0), theme.secondary);
+    }
+
+    #[test]
+    fn test_quantize_colors_pass() {
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        buf[(0, 0)]
+            .set_fg(Color::Rgb(255, 0, 0))
+            .set_bg(Color::Rgb(0, 0, 0));
+        buf[(1, 0)].set_fg(Color::Rgb(60, 70, 90));
+        quantize_colors(&mut buf, area, ColorMode::Ansi16);
+        assert_eq!(buf[(0, 0)].fg, Color::LightRed);
+        assert_eq!(buf[(0, 0)].bg, Color::Black);
+        assert!(buf[(1, 0)].modifier.contains(Modifier::DIM));
+    }
+
+    #[test]
+    fn test_mono_render_has_no_color() {
+        let mut args = test_args();
+        args.mono = true;
+        args.no_effects = false;
+        args.stars = 60;
+        let mut app = App::new(&args, 80, 24);
+        let backend = ratatui::backend::TestBackend::new(80, 24);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+        let buf = terminal.backend().buffer();
+        for cell in buf.content() {
+            assert_eq!(cell.fg, Color::Reset);
+            assert_eq!(cell.bg, Color::Reset);
+        }
+        let bold = buf
+            .content()
+            .iter()
+            .any(|c| c.modifier.contains(Modifier::BOLD));
+        assert!(bold, "bright glyphs should be bold in mono mode");
+    }
+
+    #[test]
+    fn test_transparent_render_leaves_bg_unset() {
+        let mut args = test_args();
+        args.transparent = true;
+        args.no_effects = false;
+        args.stars = 60;
+        let mut app = App::new(&args, 80, 24);
+        let backend = ratatui::backend::TestBackend::new(80, 24);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| draw(frame, &mut app)).unwrap();
+        let buf = terminal.backend().buffer();
+        assert!(buf.content().iter().all(|c| c.bg == Color::Reset));
+        assert!(buf.content().iter().any(|c| c.symbol() == "█"));
+    }
+
+    #[test]
+    fn test_effects_selection() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.stars = 100;
+        args.effects = vec![EffectKind::Scanlines];
+        let mut app = App::new(&args, 80, 24);
+        let output = render_to_string(&mut app, 80, 24);
+        assert!(!output.contains('·') && !output.contains('✦'));
+
+        args.effects = vec![EffectKind::Starfield];
+        let mut app = App::new(&args, 80, 24);
+        let output = render_to_string(&mut app, 80, 24);
+        assert!(output.contains('·') || output.contains('∘') || output.contains('✦'));
+    }
+
+    #[test]
+    fn test_warp_renders_streaks() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.stars = 60;
+        args.seed = Some(5);
+        args.effects = vec![EffectKind::Starfield];
+        let mut app = App::new(&args, 80, 24);
+        app.send_effect_event(EffectEvent::Warp);
+        for _ in 0..40 {
+            app.tick(0.05);
+        }
+        let output = render_to_string(&mut app, 80, 24);
+        assert!(
+            output.contains(['─', '│', '╲', '╱']),
+            "full warp should draw streaks"
+        );
+    }
+
+    #[test]
+    fn test_nebula_tints_background_under_stars() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.stars = 100;
+        args.seed = Some(3);
+        args.effects = vec![EffectKind::Starfield, EffectKind::Nebula];
+        let mut app = App::new(&args, 80, 24);
+        let buf = render_to_buffer(&mut app, 80, 24);
+        let bg = app.theme.bg;
+        assert!(buf.content().iter().any(|c| c.bg != bg));
+        assert!(
+            buf.content()
+                .iter()
+                .any(|c| c.bg != bg && c.symbol() != " "),
+            "stars keep the glow behind them"
+        );
+
+        args.transparent = true;
+        let mut app = App::new(&args, 80, 24);
+        let buf = render_to_buffer(&mut app, 80, 24);
+        assert!(buf.content().iter().all(|c| c.bg == Color::Reset));
+    }
+
+    #[test]
+    fn test_glow_surrounds_digits() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.effects = vec![EffectKind::Glow];
+        let mut app = App::new(&args, 80, 24);
+        let buf = render_to_buffer(&mut app, 80, 24);
+        let bg = app.theme.bg;
+        let glowing: Vec<_> = buf.content().iter().filter(|c| c.bg != bg).collect();
+        assert!(!glowing.is_empty());
+        assert!(glowing.iter().all(|c| c.symbol() == " "));
+        // Far corners stay dark
+        assert_eq!(buf[(0, 0)].bg, bg);
+        assert_eq!(buf[(79, 23)].bg, bg);
+    }
+
+    #[test]
+    fn test_clear_zone_keeps_stars_off_the_clock() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.stars = 100;
+        args.date = true;
+        args.effects = vec![EffectKind::Starfield];
+        // The digits, plus the date two rows below them
+        let width = font::scaled_rendered_width("00:00:00", 1) as u16;
+        let height = font::scaled_glyph_height(1) as u16 + 2;
+        let near_clock = Rect::new((80 - width) / 2, (24 - height) / 2, width, height);
+        let stars_near_clock = |args: &Args| {
+            let mut app = App::new(args, 80, 24);
+            let buf = render_to_buffer(&mut app, 80, 24);
+            near_clock
+                .positions()
+                .filter(|&pos| ["·", "∘", "✦"].contains(&buf[pos].symbol()))
+                .count()
+        };
+        args.clear_zone = ClearZone::Off;
+        assert!(stars_near_clock(&args) > 0);
+        args.clear_zone = ClearZone::Hide;
+        assert_eq!(stars_near_clock(&args), 0);
+    }
+
+    #[test]
+    fn test_clear_zone_grows_by_margin() {
+        let area = Rect::new(0, 0, 80, 24);
+        let bounds = Rect::new(30, 10, 20, 5);
+        assert_eq!(clear_zone(bounds, 0, area), bounds);
+        assert_eq!(clear_zone(bounds, 3, area), Rect::new(27, 8, 26, 9));
+        assert_eq!(clear_zone(bounds, 40, area), area);
+    }
+
+    #[test]
+    fn test_reduced_motion_holds_still() {
+        let mut args = test_args();
+        args.no_effects = false;
+        args.stars = 60;
+        args.blink = true;
+        args.reduced_motion = true;
+        args.effects.push(EffectKind::Crt);
+        args.crt = vec![CrtPass::Flicker, CrtPass::Persistence, CrtPass::Chromatic];
+        // A fixed face, so only the effects could change between frames
+        args.format = "12".into();
+        let mut app = App::new(&args, 60, 20);
+        assert_eq!(app.fps, 1);
+        assert!(!app.blink);
+        assert!(!app.effect_kinds.contains(&EffectKind::ShootingStars));
+
+        let before = render_to_buffer(&mut app, 60, 20);
+        app.send_effect_event(EffectEvent::Warp);
+        for _ in 0..10 {
+            app.tick(1.0);
+        }
+        assert_eq!(app.elapsed, 0.0);
+        assert_eq!(render_to_buffer(&mut app, 60, 20), before);
+
+        // A new face leaves no ghost of the old one behind
+        app.time_format = "34".into();
+        app.tick(1.0);
+        args.format = "34".into();
+        let fresh = render_to_buffer(&mut App::new(&args, 60, 20), 60, 20);
+        assert_eq!(render_to_buffer(&mut app, 60, 20), fresh);
+
+        args.reduced_motion = false;
+        app.reload(&args).unwrap();
+        assert_eq!(app.fps, 30);
+        assert!(app.blink);
+    }
+
     #[test]
     fn test_render_small_terminal() {
         // Should not panic even with tiny terminal
         let args = test_args();
-        let app = App::new(&args, 20, 5);
-        let _output = render_to_string(&app, 20, 5);
+        let mut app = App::new(&args, 20, 5);
+        let _output = render_to_string(&mut app, 20, 5);
     }
 
     #[test]
     fn test_render_scaled_size_2() {
         let mut args = test_args();
         args.size = 2;
-        let app = App::new(&args, 160, 48);
-        let output = render_to_string(&app, 160, 48);
+        let mut app = App::new(&args, 160, 48);
+        let output = render_to_string(&mut app, 160, 48);
         assert!(output.contains('█'), "scaled render should contain block chars");
     }
 
@@ -282,7 +487,7 @@ mod tests {
         // Should not panic even if terminal is smaller than scaled clock
         let mut args = test_args();
         args.size = 3;
-        let app = App::new(&args, 40, 10);
-        let _output = render_to_string(&app, 40, 10);
+        let mut app = App::new(&args, 40, 10);
+        let _output = render_to_string(&mut app, 40, 10);
     }
 }
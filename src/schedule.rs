@@ -0,0 +1,108 @@
+//! Automatic day/night theme switching.
+//!
+//! With a location, the blend follows the real sun: fully night below civil
+//! twilight, fully day once the sun has risen, crossfading in between. Without
+//! one, fixed clock hours are used with a half-hour fade.
+
+use chrono::{DateTime, Local, NaiveTime, Timelike, Utc};
+
+use crate::astro::{self, Location, CIVIL_TWILIGHT_ELEVATION, SUNRISE_ELEVATION};
+use crate::theme::Theme;
+
+/// Hour at which the fallback schedule finishes fading to the day theme.
+const DAY_START_HOUR: u32 = 7;
+
+/// Hour at which the fallback schedule starts fading to the night theme.
+const NIGHT_START_HOUR: u32 = 19;
+
+/// Length of the fallback crossfade in minutes.
+const FALLBACK_FADE_MINUTES: f32 = 30.0;
+
+/// Chooses between a day and a night theme based on the time of day.
+#[derive(Debug, Clone)]
+pub struct ThemeSchedule {
+    pub day: Theme,
+    pub night: Theme,
+    pub location: Option<Location>,
+}
+
+impl ThemeSchedule {
+    pub fn new(day: Theme, night: Theme, location: Option<Location>) -> Self {
+        Self {
+            day,
+            night,
+            location,
+        }
+    }
+
+    /// How much of the day theme to show at `now` (0.0 = night, 1.0 = day).
+    pub fn daylight(&self, now: DateTime<Local>) -> f32 {
+        match self.location {
+            Some(loc) => {
+                daylight_from_elevation(astro::sun_elevation(now.with_timezone(&Utc), loc))
+            }
+            None => daylight_from_clock(now.time()),
+        }
+    }
+
+    /// The (possibly blended) theme to display at `now`.
+    pub fn theme_at(&self, now: DateTime<Local>) -> Theme {
+        Theme::lerp(&self.night, &self.day, self.daylight(now))
+    }
+}
+
+/// Map solar elevation onto the twilight crossfade.
+fn daylight_from_elevation(elevation: f64) -> f32 {
+    let span = SUNRISE_ELEVATION - CIVIL_TWILIGHT_ELEVATION;
+    ((elevation - CIVIL_TWILIGHT_ELEVATION) / span).clamp(0.0, 1.0) as f32
+}
+
+/// Fixed-hour fallback: fade in before `DAY_START_HOUR`, fade out after
+/// `NIGHT_START_HOUR`.
+fn daylight_from_clock(time: NaiveTime) -> f32 {
+    let minutes = (time.hour() * 60 + time.minute()) as f32 + time.second() as f32 / 60.0;
+    let day_start = (DAY_START_HOUR * 60) as f32;
+    let night_start = (NIGHT_START_HOUR * 60) as f32;
+    let fade_in = (minutes - (day_start - FALLBACK_FADE_MINUTES)) / FALLBACK_FADE_MINUTES;
+    let fade_out = 1.0 - (minutes - night_start) / FALLBACK_FADE_MINUTES;
+    fade_in.min(fade_out).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_clock_fallback_day_and_night() {
+        assert_eq!(daylight_from_clock(at(12, 0)), 1.0);
+        assert_eq!(daylight_from_clock(at(2, 0)), 0.0);
+        assert_eq!(daylight_from_clock(at(23, 30)), 0.0);
+    }
+
+    #[test]
+    fn test_clock_fallback_fades() {
+        let dawn = daylight_from_clock(at(6, 45));
+        assert!(dawn > 0.4 && dawn < 0.6, "dawn = {dawn}");
+        let dusk = daylight_from_clock(at(19, 15));
+        assert!(dusk > 0.4 && dusk < 0.6, "dusk = {dusk}");
+    }
+
+    #[test]
+    fn test_elevation_mapping() {
+        assert_eq!(daylight_from_elevation(30.0), 1.0);
+        assert_eq!(daylight_from_elevation(-12.0), 0.0);
+        let mid = daylight_from_elevation(-3.4);
+        assert!(mid > 0.0 && mid < 1.0);
+    }
+
+    #[test]
+    fn test_theme_at_uses_day_theme_at_noon() {
+        let sched = ThemeSchedule::new(Theme::solar(), Theme::void(), None);
+        let noon = Local::now().with_hour(12).unwrap().with_minute(0).unwrap();
+        assert_eq!(sched.theme_at(noon).accent, Theme::solar().accent);
+    }
+}
@@ -1,9 +1,30 @@
+use std::path::{Path, PathBuf};
+
 use ratatui::style::Color;
 
+use crate::config;
+use crate::named_colors;
+
+/// Names of the built-in themes, in display order.
+pub const BUILTIN_THEMES: [&str; 4] = ["void", "nebula", "luna", "solar"];
+
+/// Keys naming each theme color, as used in theme files and `--theme-set`.
+pub const COLOR_KEYS: [&str; 9] = [
+    "accent",
+    "secondary",
+    "date_color",
+    "star_bright",
+    "star_dim",
+    "shooting_star",
+    "scanline",
+    "aurora",
+    "bg",
+];
+
 /// A color theme for stardial.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
-    pub name: &'static str,
+    pub name: String,
     /// Primary accent color for digits
     pub accent: Color,
     /// Secondary color for colon / dim elements
@@ -18,24 +39,267 @@ pub struct Theme {
     pub shooting_star: Color,
     /// Scanline tint
     pub scanline: Color,
+    /// Tint of the aurora / nebula background glow
+    pub aurora: Color,
     /// Background color (usually near-black)
     pub bg: Color,
+    /// Optional multi-stop gradient across the digits
+    pub gradient: Option<Gradient>,
+}
+
+/// How gradient stops are laid out across the clock face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GradientMode {
+    /// Left to right
+    Horizontal,
+    /// Top to bottom
+    Vertical,
+    /// Top-left to bottom-right
+    Diagonal,
+    /// Stops cycle continuously from right to left
+    Rainbow,
+}
+
+impl GradientMode {
+    fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "horizontal" => Some(Self::Horizontal),
+            "vertical" => Some(Self::Vertical),
+            "diagonal" => Some(Self::Diagonal),
+            "rainbow" => Some(Self::Rainbow),
+            _ => None,
+        }
+    }
+}
+
+/// A color gradient applied across the digit cells.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gradient {
+    pub mode: GradientMode,
+    /// Colors evenly spaced along the gradient axis
+    pub stops: Vec<Color>,
+}
+
+/// Rainbow sweep speed in gradient lengths per second.
+const RAINBOW_SPEED: f32 = 0.15;
+
+impl Gradient {
+    /// Color at a cell position. `u` and `v` are the horizontal and vertical
+    /// position within the clock face (0.0-1.0); `elapsed` animates the
+    /// rainbow sweep.
+    pub fn color_at(&self, u: f32, v: f32, elapsed: f32) -> Color {
+        match self.stops.len() {
+            0 => Color::Reset,
+            1 => self.stops[0],
+            n => {
+                let pos = match self.mode {
+                    GradientMode::Horizontal => u,
+                    GradientMode::Vertical => v,
+                    GradientMode::Diagonal => (u + v) / 2.0,
+                    GradientMode::Rainbow => {
+                        // Wrap around through the first stop again so the sweep is seamless
+                        let p = (u + elapsed * RAINBOW_SPEED).rem_euclid(1.0) * n as f32;
+                        let i = (p as usize).min(n - 1);
+                        return lerp_color(self.stops[i], self.stops[(i + 1) % n], p.fract());
+                    }
+                };
+                let p = pos.clamp(0.0, 1.0) * (n - 1) as f32;
+                let i = (p as usize).min(n - 2);
+                lerp_color(self.stops[i], self.stops[i + 1], p - i as f32)
+            }
+        }
+    }
+
+    /// Blend two gradients stop-by-stop. Gradients with a different number
+    /// of stops cannot be blended and switch over at the halfway point.
+    fn lerp(a: &Option<Gradient>, b: &Option<Gradient>, t: f32) -> Option<Gradient> {
+        match (a, b) {
+            (Some(ga), Some(gb)) if ga.stops.len() == gb.stops.len() => Some(Gradient {
+                mode: if t < 0.5 { ga.mode } else { gb.mode },
+                stops: ga
+                    .stops
+                    .iter()
+                    .zip(&gb.stops)
+                    .map(|(&ca, &cb)| lerp_color(ca, cb, t))
+                    .collect(),
+            }),
+            _ if t < 0.5 => a.clone(),
+            _ => b.clone(),
+        }
+    }
 }
 
 impl Theme {
+    /// Look up a built-in or user theme, falling back to `void`.
     pub fn by_name(name: &str) -> Self {
+        Self::builtin(name)
+            .or_else(|| Self::load(name).ok())
+            .unwrap_or_else(Self::void)
+    }
+
+    /// Look up a built-in theme by name.
+    pub fn builtin(name: &str) -> Option<Self> {
         match name {
-            "nebula" => Self::nebula(),
-            "luna" => Self::luna(),
-            "solar" => Self::solar(),
-            _ => Self::void(),
+            "void" => Some(Self::void()),
+            "nebula" => Some(Self::nebula()),
+            "luna" => Some(Self::luna()),
+            "solar" => Some(Self::solar()),
+            _ => None,
         }
     }
 
+    /// Resolve a theme by built-in name, user theme name, or path to a
+    /// theme file.
+    pub fn load(name: &str) -> Result<Self, String> {
+        if let Some(theme) = Self::builtin(name) {
+            return Ok(theme);
+        }
+        let path = theme_path(name).ok_or_else(|| unknown_theme(name))?;
+        if !path.is_file() {
+            return Err(unknown_theme(name));
+        }
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or(name);
+        Self::from_toml(stem, &contents).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// Parse a theme file. Missing colors come from the `base` theme
+    /// (default `void`); `name` is used unless the file sets its own.
+    ///
+    /// ```toml
+    /// name = "sakura"
+    /// base = "nebula"
+    /// accent = "#ffb7c5"
+    ///
+    /// [gradient]
+    /// mode = "horizontal"
+    /// stops = ["#ffb7c5", "#ff6ac1", "#b450a0"]
+    /// ```
+    pub fn from_toml(name: &str, contents: &str) -> Result<Self, String> {
+        let table: toml::Table = contents
+            .parse()
+            .map_err(|e: toml::de::Error| e.message().to_string())?;
+
+        let base = table
+            .iter()
+            .find_map(|(key, value)| (normalize_key(key) == "base").then_some(value));
+        let mut theme = match base {
+            Some(toml::Value::String(base)) => {
+                Self::builtin(base).ok_or_else(|| format!("unknown base theme `{base}`"))?
+            }
+            Some(_) => return Err("`base` must be a string".into()),
+            None => Self::void(),
+        };
+        theme.name = name.to_string();
+
+        for (key, value) in &table {
+            match normalize_key(key).as_str() {
+                "base" => {}
+                "name" => {
+                    theme.name = value.as_str().ok_or("`name` must be a string")?.to_string();
+                }
+                "gradient" => {
+                    let section = value.as_table().ok_or("`gradient` must be a table")?;
+                    theme.gradient = Some(parse_gradient(section)?);
+                }
+                color_key => {
+                    let s = value
+                        .as_str()
+                        .ok_or_else(|| format!("`{key}` must be a string"))?;
+                    let color = parse_color(s).map_err(|e| format!("`{key}`: {e}"))?;
+                    theme.set_color(color_key, color)?;
+                }
+            }
+        }
+        Ok(theme)
+    }
+
+    /// Mutable access to a color slot by its theme-file key; mirrors
+    /// [`Theme::color`].
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        match key {
+            "accent" => Some(&mut self.accent),
+            "secondary" => Some(&mut self.secondary),
+            "date_color" => Some(&mut self.date_color),
+            "star_bright" => Some(&mut self.star_bright),
+            "star_dim" => Some(&mut self.star_dim),
+            "shooting_star" => Some(&mut self.shooting_star),
+            "aurora" => Some(&mut self.aurora),
+            "scanline" => Some(&mut self.scanline),
+            "bg" => Some(&mut self.bg),
+            _ => None,
+        }
+    }
+
+    /// Look up a color by key (see [`COLOR_KEYS`]).
+    pub fn color(&self, key: &str) -> Option<&Color> {
+        match key {
+            "accent" => Some(&self.accent),
+            "secondary" => Some(&self.secondary),
+            "date_color" => Some(&self.date_color),
+            "star_bright" => Some(&self.star_bright),
+            "star_dim" => Some(&self.star_dim),
+            "shooting_star" => Some(&self.shooting_star),
+            "aurora" => Some(&self.aurora),
+            "scanline" => Some(&self.scanline),
+            "bg" => Some(&self.bg),
+            _ => None,
+        }
+    }
+
+    /// Serialize as a theme file that [`Theme::from_toml`] reads back.
+    pub fn to_toml(&self) -> String {
+        let name = toml::Value::String(self.name.clone());
+        let mut out = format!("name = {name}\n");
+        for key in COLOR_KEYS {
+            if let Some(color) = self.color(key).copied().and_then(format_color) {
+                out.push_str(&format!("{key} = \"{color}\"\n"));
+            }
+        }
+        if let Some(ref g) = self.gradient {
+            let mode = match g.mode {
+                GradientMode::Horizontal => "horizontal",
+                GradientMode::Vertical => "vertical",
+                GradientMode::Diagonal => "diagonal",
+                GradientMode::Rainbow => "rainbow",
+            };
+            let stops: Vec<String> = g
+                .stops
+                .iter()
+                .filter_map(|&c| format_color(c))
+                .map(|c| format!("\"{c}\""))
+                .collect();
+            out.push_str(&format!(
+                "\n[gradient]\nmode = \"{mode}\"\nstops = [{}]\n",
+                stops.join(", ")
+            ));
+        }
+        out
+    }
+
+    /// Set a color by key (see [`COLOR_KEYS`]).
+    pub fn set_color(&mut self, key: &str, color: Color) -> Result<(), String> {
+        let slot = self.color_mut(key).ok_or_else(|| unknown_color_key(key))?;
+        *slot = color;
+        Ok(())
+    }
+
+    /// Use a gradient in `mode`, keeping the theme's own stops if it has
+    /// any and otherwise fading from accent to secondary.
+    pub fn with_gradient_mode(mut self, mode: GradientMode) -> Self {
+        let stops = match self.gradient.take() {
+            Some(g) => g.stops,
+            None => vec![self.accent, self.secondary],
+        };
+        self.gradient = Some(Gradient { mode, stops });
+        self
+    }
+
     /// Deep space — cool cyan/blue on black
     pub fn void() -> Self {
         Self {
-            name: "void",
+            name: "void".into(),
             accent: Color::Rgb(0, 255, 255),
             secondary: Color::Rgb(0, 160, 180),
             date_color: Color::Rgb(80, 120, 140),
@@ -43,14 +307,16 @@ impl Theme {
             star_dim: Color::Rgb(60, 70, 90),
             shooting_star: Color::Rgb(180, 220, 255),
             scanline: Color::Rgb(0, 40, 50),
+            aurora: Color::Rgb(40, 220, 140),
             bg: Color::Rgb(0, 0, 0),
+            gradient: None,
         }
     }
 
     /// Purple-pink nebula haze
     pub fn nebula() -> Self {
         Self {
-            name: "nebula",
+            name: "nebula".into(),
             accent: Color::Rgb(255, 106, 193),
             secondary: Color::Rgb(180, 80, 160),
             date_color: Color::Rgb(140, 80, 130),
@@ -58,14 +324,16 @@ impl Theme {
             star_dim: Color::Rgb(90, 50, 80),
             shooting_star: Color::Rgb(255, 150, 220),
             scanline: Color::Rgb(40, 0, 30),
+            aurora: Color::Rgb(255, 90, 170),
             bg: Color::Rgb(5, 0, 10),
+            gradient: None,
         }
     }
 
     /// Soft silver-blue moonlight
     pub fn luna() -> Self {
         Self {
-            name: "luna",
+            name: "luna".into(),
             accent: Color::Rgb(200, 210, 240),
             secondary: Color::Rgb(140, 150, 180),
             date_color: Color::Rgb(100, 110, 140),
@@ -73,14 +341,16 @@ impl Theme {
             star_dim: Color::Rgb(50, 55, 70),
             shooting_star: Color::Rgb(210, 220, 255),
             scanline: Color::Rgb(15, 15, 25),
+            aurora: Color::Rgb(120, 140, 230),
             bg: Color::Rgb(2, 2, 8),
+            gradient: None,
         }
     }
 
     /// Warm golden sun
     pub fn solar() -> Self {
         Self {
-            name: "solar",
+            name: "solar".into(),
             accent: Color::Rgb(255, 200, 50),
             secondary: Color::Rgb(200, 150, 40),
             date_color: Color::Rgb(160, 120, 50),
@@ -88,66 +358,595 @@ impl Theme {
             star_dim: Color::Rgb(80, 70, 40),
             shooting_star: Color::Rgb(255, 220, 100),
             scanline: Color::Rgb(30, 20, 0),
+            aurora: Color::Rgb(255, 130, 40),
             bg: Color::Rgb(5, 2, 0),
+            gradient: None,
         }
     }
 
-    /// Override the accent color from a user-supplied string.
-    pub fn with_accent_override(mut self, color_str: &str) -> Self {
-        if let Some(c) = parse_color(color_str) {
-            self.accent = c;
+    /// Blend two themes: `t = 0.0` yields `a`, `t = 1.0` yields `b`.
+    /// The name follows whichever theme is closer.
+    pub fn lerp(a: &Theme, b: &Theme, t: f32) -> Theme {
+        let t = t.clamp(0.0, 1.0);
+        Theme {
+            name: if t < 0.5 {
+                a.name.clone()
+            } else {
+                b.name.clone()
+            },
+            accent: lerp_color(a.accent, b.accent, t),
+            secondary: lerp_color(a.secondary, b.secondary, t),
+            date_color: lerp_color(a.date_color, b.date_color, t),
+            star_bright: lerp_color(a.star_bright, b.star_bright, t),
+            star_dim: lerp_color(a.star_dim, b.star_dim, t),
+            shooting_star: lerp_color(a.shooting_star, b.shooting_star, t),
+            aurora: lerp_color(a.aurora, b.aurora, t),
+            scanline: lerp_color(a.scanline, b.scanline, t),
+            bg: lerp_color(a.bg, b.bg, t),
+            gradient: Gradient::lerp(&a.gradient, &b.gradient, t),
         }
+    }
+
+    /// Override the accent color.
+    pub fn with_accent_override(mut self, color: Color) -> Self {
+        self.accent = color;
         self
     }
 }
 
-/// Parse a color from a name or #RRGGBB hex string.
-pub fn parse_color(s: &str) -> Option<Color> {
-    let s = s.trim();
-    // Hex
-    if let Some(hex) = s.strip_prefix('#') {
-        if hex.len() == 6 {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            return Some(Color::Rgb(r, g, b));
-        }
-    }
-    // Named colors
-    match s.to_lowercase().as_str() {
-        "red" => Some(Color::Red),
-        "green" => Some(Color::Green),
-        "blue" => Some(Color::Blue),
-        "cyan" => Some(Color::Cyan),
-        "magenta" => Some(Color::Magenta),
-        "yellow" => Some(Color::Yellow),
-        "white" => Some(Color::White),
-        "black" => Some(Color::Black),
+/// User tweaks layered on top of whichever theme is active, so they
+/// survive theme switches.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeOverrides {
+    pub accent: Option<Color>,
+    /// Individual colors set by key, applied in order after `accent`
+    pub colors: Vec<(String, Color)>,
+    pub gradient: Option<GradientMode>,
+}
+
+impl ThemeOverrides {
+    pub fn apply(&self, mut theme: Theme) -> Theme {
+        if let Some(c) = self.accent {
+            theme = theme.with_accent_override(c);
+        }
+        for (key, color) in &self.colors {
+            if let Err(e) = theme.set_color(key, *color) {
+                tracing::warn!("ignoring theme override: {e}");
+            }
+        }
+        if let Some(mode) = self.gradient {
+            theme = theme.with_gradient_mode(mode);
+        }
+        theme
+    }
+}
+
+fn parse_gradient(section: &toml::Table) -> Result<Gradient, String> {
+    let mode = match section.get("mode") {
+        Some(v) => {
+            let s = v.as_str().ok_or("`gradient.mode` must be a string")?;
+            GradientMode::parse(s).ok_or_else(|| format!("unknown gradient mode `{s}`"))?
+        }
+        None => GradientMode::Horizontal,
+    };
+    let stops = section
+        .get("stops")
+        .and_then(|v| v.as_array())
+        .ok_or("`gradient.stops` must be a list of colors")?
+        .iter()
+        .map(|v| {
+            let s = v.as_str().ok_or("gradient stops must be strings")?;
+            parse_color(s).map_err(|e| format!("gradient stop: {e}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if stops.len() < 2 {
+        return Err("`gradient.stops` needs at least two colors".into());
+    }
+    Ok(Gradient { mode, stops })
+}
+
+fn unknown_color_key(key: &str) -> String {
+    format!(
+        "unknown key `{key}` (expected one of: {})",
+        COLOR_KEYS.join(", ")
+    )
+}
+
+/// A key as a user wrote it, in the form of [`COLOR_KEYS`]: case is
+/// ignored and hyphens stand for underscores.
+fn normalize_key(key: &str) -> String {
+    key.trim().to_ascii_lowercase().replace('-', "_")
+}
+
+/// Parse a `KEY=COLOR` override such as `bg=#101020`. Keys are matched
+/// like theme file keys (see [`normalize_key`]).
+pub fn parse_color_setting(s: &str) -> Result<(String, Color), String> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=COLOR, got `{s}`"))?;
+    let key = normalize_key(key);
+    if !COLOR_KEYS.contains(&key.as_str()) {
+        return Err(unknown_color_key(&key));
+    }
+    Ok((key, parse_color(value)?))
+}
+
+fn unknown_theme(name: &str) -> String {
+    format!(
+        "unknown theme `{name}` (built-in themes: {})",
+        BUILTIN_THEMES.join(", ")
+    )
+}
+
+/// Directory searched for user theme files:
+/// `$XDG_CONFIG_HOME/stardial/themes`, or `~/.config/stardial/themes`.
+pub fn themes_dir() -> Option<PathBuf> {
+    Some(config::config_dir()?.join("themes"))
+}
+
+/// File a non-built-in theme `name` would be read from: `name` itself when
+/// it looks like a path, otherwise `name.toml` in [`themes_dir`].
+pub fn theme_path(name: &str) -> Option<PathBuf> {
+    if Theme::builtin(name).is_some() {
+        return None;
+    }
+    let path = Path::new(name);
+    if path.extension().is_some_and(|e| e == "toml") || path.components().count() > 1 {
+        Some(path.to_path_buf())
+    } else {
+        Some(themes_dir()?.join(format!("{name}.toml")))
+    }
+}
+
+/// Names of the theme files in [`themes_dir`], sorted. Built-in names are
+/// skipped since those always resolve to the built-in theme.
+pub fn user_theme_names() -> Vec<String> {
+    let Some(entries) = themes_dir().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            Some(path.file_stem()?.to_str()?.to_string())
+        })
+        .filter(|name| !BUILTIN_THEMES.contains(&name.as_str()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// A crossfade from a snapshot of the previous theme towards the current one.
+///
+/// The target is supplied on every call to [`ThemeTransition::apply`] so that
+/// moving targets (such as a day/night schedule) blend correctly too.
+#[derive(Debug, Clone)]
+pub struct ThemeTransition {
+    from: Theme,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl ThemeTransition {
+    pub fn new(from: Theme, duration: f32) -> Self {
+        Self {
+            from,
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    /// Advance the transition clock by `dt` seconds.
+    pub fn advance(&mut self, dt: f32) {
+        self.elapsed += dt;
+    }
+
+    /// Eased progress in 0.0-1.0.
+    pub fn progress(&self) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        let t = (self.elapsed / self.duration).clamp(0.0, 1.0);
+        // Smoothstep: gentle start and finish
+        t * t * (3.0 - 2.0 * t)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// The blended theme between the snapshot and `to`.
+    pub fn apply(&self, to: &Theme) -> Theme {
+        Theme::lerp(&self.from, to, self.progress())
+    }
+}
+
+/// Standard xterm values for the 16 ANSI colors, indexed like `Color::Indexed`.
+pub const ANSI_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Approximate RGB value of any color. Named and indexed colors use the
+/// xterm defaults; `Reset` has no fixed value and returns `None`.
+pub fn color_rgb(c: Color) -> Option<(u8, u8, u8)> {
+    let ansi = |i: usize| Some(ANSI_PALETTE[i]);
+    match c {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Black => ansi(0),
+        Color::Red => ansi(1),
+        Color::Green => ansi(2),
+        Color::Yellow => ansi(3),
+        Color::Blue => ansi(4),
+        Color::Magenta => ansi(5),
+        Color::Cyan => ansi(6),
+        Color::Gray => ansi(7),
+        Color::DarkGray => ansi(8),
+        Color::LightRed => ansi(9),
+        Color::LightGreen => ansi(10),
+        Color::LightYellow => ansi(11),
+        Color::LightBlue => ansi(12),
+        Color::LightMagenta => ansi(13),
+        Color::LightCyan => ansi(14),
+        Color::White => ansi(15),
+        Color::Indexed(i) => Some(indexed_rgb(i)),
+        Color::Reset => None,
+    }
+}
+
+/// RGB value of an xterm 256-color palette index.
+fn indexed_rgb(i: u8) -> (u8, u8, u8) {
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match i {
+        0..=15 => ANSI_PALETTE[i as usize],
+        16..=231 => {
+            let i = i - 16;
+            (
+                CUBE[(i / 36) as usize],
+                CUBE[(i / 6 % 6) as usize],
+                CUBE[(i % 6) as usize],
+            )
+        }
+        _ => {
+            let v = 8 + (i - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+/// Interpolate two colors channel-wise, resolving named and indexed colors
+/// to RGB first. `Reset` cannot be blended, so it switches over at the
+/// halfway point.
+pub fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    match (color_rgb(a), color_rgb(b)) {
+        (Some((ar, ag, ab)), Some((br, bg, bb))) => {
+            Color::Rgb(lerp_u8(ar, br, t), lerp_u8(ag, bg, t), lerp_u8(ab, bb, t))
+        }
+        _ if t < 0.5 => a,
+        _ => b,
+    }
+}
+
+fn lerp_u8(a: u8, b: u8, t: f32) -> u8 {
+    let v = a as f32 + (b as f32 - a as f32) * t;
+    v.round().clamp(0.0, 255.0) as u8
+}
+
+/// Basic names that stay ANSI palette colors, so they follow the user's
+/// terminal color scheme rather than fixed CSS values.
+const ANSI_NAMES: [(&str, Color); 8] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("white", Color::White),
+];
+
+/// Parse a color from any supported notation:
+///
+/// - `#RGB`, `#RRGGBB`, or `#RRGGBBAA` (alpha is applied over black)
+/// - `rgb(255, 106, 193)`, with optional percentages and alpha
+/// - `hsl(320, 100%, 71%)`, with optional alpha
+/// - `ansi:123` for an xterm 256-color palette index
+/// - a CSS/X11 color name such as `hotpink` or `skyblue3`; the eight basic
+///   names (`red`, `cyan`, ...) map to the terminal's own palette
+///
+/// Errors explain what was expected and suggest the closest color name.
+pub fn parse_color(s: &str) -> Result<Color, String> {
+    let input = s.trim();
+    let lower = input.to_lowercase();
+
+    if let Some(hex) = lower.strip_prefix('#') {
+        return parse_hex(hex).ok_or_else(|| {
+            format!("invalid hex color `{input}` (expected #RGB, #RRGGBB or #RRGGBBAA)")
+        });
+    }
+    if let Some(index) = lower.strip_prefix("ansi:") {
+        return index
+            .trim()
+            .parse()
+            .map(Color::Indexed)
+            .map_err(|_| format!("invalid palette index `{input}` (expected ansi:0 to ansi:255)"));
+    }
+    if let Some(args) = function_args(&lower, "rgb") {
+        return parse_rgb_function(args)
+            .ok_or_else(|| format!("invalid color `{input}` (expected rgb(R, G, B) with 0-255)"));
+    }
+    if let Some(args) = function_args(&lower, "hsl") {
+        return parse_hsl_function(args)
+            .ok_or_else(|| format!("invalid color `{input}` (expected hsl(H, S%, L%))"));
+    }
+
+    if let Some(&(_, c)) = ANSI_NAMES.iter().find(|(n, _)| *n == lower) {
+        return Ok(c);
+    }
+    // Accept "sky blue", "sky-blue" and "sky_blue" for "skyblue"
+    let compact: String = lower
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .collect();
+    if let Some((r, g, b)) = named_colors::lookup(&compact) {
+        return Ok(Color::Rgb(r, g, b));
+    }
+
+    Err(match closest_color_name(&compact) {
+        Some(name) => format!("unknown color `{input}` (did you mean `{name}`?)"),
+        None => format!("unknown color `{input}`"),
+    })
+}
+
+/// Format a color in a notation [`parse_color`] accepts. `Reset` has no
+/// notation and yields `None`.
+pub fn format_color(color: Color) -> Option<String> {
+    match color {
+        Color::Rgb(r, g, b) => Some(format!("#{r:02x}{g:02x}{b:02x}")),
+        Color::Indexed(i) => Some(format!("ansi:{i}")),
+        Color::Reset => None,
+        c => match ANSI_NAMES.iter().find(|(_, ansi)| *ansi == c) {
+            Some((name, _)) => Some(name.to_string()),
+            None => color_rgb(c).map(|(r, g, b)| format!("#{r:02x}{g:02x}{b:02x}")),
+        },
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => {
+            let nibble = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+            Some(Color::Rgb(nibble(0)?, nibble(1)?, nibble(2)?))
+        }
+        6 => Some(Color::Rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Some(with_alpha(
+            (byte(0)?, byte(2)?, byte(4)?),
+            byte(6)? as f32 / 255.0,
+        )),
         _ => None,
     }
 }
 
+/// Terminals have no alpha channel, so premultiply against black.
+fn with_alpha((r, g, b): (u8, u8, u8), alpha: f32) -> Color {
+    let a = alpha.clamp(0.0, 1.0);
+    let scale = |v: u8| (v as f32 * a).round() as u8;
+    Color::Rgb(scale(r), scale(g), scale(b))
+}
+
+/// The argument list of `name(...)` or `namea(...)`, if `s` has that form.
+fn function_args<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    let rest = s.strip_prefix(name)?;
+    let rest = rest.strip_prefix('a').unwrap_or(rest);
+    rest.trim_start().strip_prefix('(')?.strip_suffix(')')
+}
+
+/// Split CSS function arguments on commas, whitespace or `/`.
+fn split_args(args: &str) -> Vec<&str> {
+    args.split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// A number, or a percentage scaled so that 100% equals `full`.
+fn parse_component(s: &str, full: f32) -> Option<f32> {
+    match s.strip_suffix('%') {
+        Some(pct) => pct.parse::<f32>().ok().map(|v| v / 100.0 * full),
+        None => s.parse().ok(),
+    }
+}
+
+fn parse_alpha(args: &[&str], index: usize) -> Option<f32> {
+    match args.get(index) {
+        Some(a) => parse_component(a, 1.0).filter(|a| (0.0..=1.0).contains(a)),
+        None => Some(1.0),
+    }
+}
+
+fn parse_rgb_function(args: &str) -> Option<Color> {
+    let args = split_args(args);
+    if !(3..=4).contains(&args.len()) {
+        return None;
+    }
+    let channel = |i: usize| {
+        parse_component(args[i], 255.0)
+            .filter(|v| (0.0..=255.0).contains(v))
+            .map(|v| v.round() as u8)
+    };
+    let rgb = (channel(0)?, channel(1)?, channel(2)?);
+    Some(with_alpha(rgb, parse_alpha(&args, 3)?))
+}
+
+fn parse_hsl_function(args: &str) -> Option<Color> {
+    let args = split_args(args);
+    if !(3..=4).contains(&args.len()) {
+        return None;
+    }
+    let hue: f32 = args[0]
+        .strip_suffix("deg")
+        .unwrap_or(args[0])
+        .parse()
+        .ok()?;
+    let percent = |i: usize| {
+        let v: f32 = args[i].strip_suffix('%').unwrap_or(args[i]).parse().ok()?;
+        (0.0..=100.0).contains(&v).then_some(v / 100.0)
+    };
+    let rgb = hsl_to_rgb(hue, percent(1)?, percent(2)?);
+    Some(with_alpha(rgb, parse_alpha(&args, 3)?))
+}
+
+/// Convert HSL (hue in degrees, saturation and lightness in 0.0-1.0) to RGB.
+pub fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (u8, u8, u8) {
+    let h = hue.rem_euclid(360.0) / 60.0;
+    let c = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = lightness - c / 2.0;
+    let to_u8 = |v: f32| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+/// The known color name closest to `name` by edit distance, if any is
+/// close enough to be a plausible typo.
+fn closest_color_name(name: &str) -> Option<&'static str> {
+    if name.is_empty() {
+        return None;
+    }
+    let limit = (name.len() / 3).max(2);
+    ANSI_NAMES
+        .iter()
+        .map(|(n, _)| *n)
+        .chain(named_colors::NAMED_COLORS.iter().map(|(n, _)| *n))
+        .map(|n| (edit_distance(name, n), n))
+        .filter(|&(d, _)| d <= limit)
+        .min_by_key(|&(d, _)| d)
+        .map(|(_, n)| n)
+}
+
+/// Levenshtein distance between two ASCII-ish strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_parse_hex_color() {
-        assert_eq!(parse_color("#ff6ac1"), Some(Color::Rgb(255, 106, 193)));
-        assert_eq!(parse_color("#000000"), Some(Color::Rgb(0, 0, 0)));
-        assert_eq!(parse_color("#ffffff"), Some(Color::Rgb(255, 255, 255)));
+        assert_eq!(parse_color("#ff6ac1").ok(), Some(Color::Rgb(255, 106, 193)));
+        assert_eq!(parse_color("#000000").ok(), Some(Color::Rgb(0, 0, 0)));
+        assert_eq!(parse_color("#ffffff").ok(), Some(Color::Rgb(255, 255, 255)));
     }
 
     #[test]
     fn test_parse_named_color() {
-        assert_eq!(parse_color("red"), Some(Color::Red));
-        assert_eq!(parse_color("Cyan"), Some(Color::Cyan));
+        assert_eq!(parse_color("red").ok(), Some(Color::Red));
+        assert_eq!(parse_color("Cyan").ok(), Some(Color::Cyan));
     }
 
     #[test]
     fn test_parse_invalid_color() {
-        assert_eq!(parse_color("notacolor"), None);
-        assert_eq!(parse_color("#xyz"), None);
+        assert_eq!(parse_color("notacolor").ok(), None);
+        assert_eq!(parse_color("#xyz").ok(), None);
+    }
+
+    #[test]
+    fn test_parse_short_and_alpha_hex() {
+        assert_eq!(parse_color("#f0a"), Ok(Color::Rgb(255, 0, 170)));
+        assert_eq!(parse_color("#ff000080"), Ok(Color::Rgb(128, 0, 0)));
+        assert_eq!(parse_color("#FF6AC1"), Ok(Color::Rgb(255, 106, 193)));
+        assert!(parse_color("#ff6ac").is_err());
+    }
+
+    #[test]
+    fn test_parse_rgb_function() {
+        assert_eq!(
+            parse_color("rgb(255, 106, 193)"),
+            Ok(Color::Rgb(255, 106, 193))
+        );
+        assert_eq!(parse_color("rgb(100% 0% 50%)"), Ok(Color::Rgb(255, 0, 128)));
+        assert_eq!(
+            parse_color("rgba(255, 255, 255, 0.5)"),
+            Ok(Color::Rgb(128, 128, 128))
+        );
+        assert!(parse_color("rgb(256, 0, 0)").is_err());
+        assert!(parse_color("rgb(1, 2)").is_err());
+    }
+
+    #[test]
+    fn test_parse_hsl_function() {
+        assert_eq!(parse_color("hsl(0, 100%, 50%)"), Ok(Color::Rgb(255, 0, 0)));
+        assert_eq!(
+            parse_color("hsl(120deg 100% 25%)"),
+            Ok(Color::Rgb(0, 128, 0))
+        );
+        assert_eq!(
+            parse_color("hsl(0, 0%, 100%)"),
+            Ok(Color::Rgb(255, 255, 255))
+        );
+        assert!(parse_color("hsl(0, 150%, 50%)").is_err());
+    }
+
+    #[test]
+    fn test_parse_ansi_index() {
+        assert_eq!(parse_color("ansi:123"), Ok(Color::Indexed(123)));
+        assert_eq!(parse_color("ANSI:0"), Ok(Color::Indexed(0)));
+        assert!(parse_color("ansi:256").is_err());
+    }
+
+    #[test]
+    fn test_parse_css_names() {
+        assert_eq!(parse_color("hotpink"), Ok(Color::Rgb(255, 105, 180)));
+        assert_eq!(parse_color("Rebecca Purple"), Ok(Color::Rgb(102, 51, 153)));
+        assert_eq!(parse_color("deep-sky-blue"), Ok(Color::Rgb(0, 191, 255)));
+    }
+
+    #[test]
+    fn test_invalid_color_suggests_name() {
+        let err = parse_color("hotpnk").unwrap_err();
+        assert!(err.contains("did you mean `hotpink`"), "{err}");
+        let err = parse_color("bleu").unwrap_err();
+        assert!(err.contains("did you mean `blue`"), "{err}");
+        let err = parse_color("zzzzzzzzzzzz").unwrap_err();
+        assert!(!err.contains("did you mean"), "{err}");
     }
 
     #[test]
@@ -158,9 +957,221 @@ mod tests {
         assert_eq!(t.name, "void"); // fallback
     }
 
+    #[test]
+    fn test_lerp_endpoints() {
+        let a = Theme::void();
+        let b = Theme::solar();
+        let start = Theme::lerp(&a, &b, 0.0);
+        let end = Theme::lerp(&a, &b, 1.0);
+        assert_eq!(start.accent, a.accent);
+        assert_eq!(start.name, "void");
+        assert_eq!(end.bg, b.bg);
+        assert_eq!(end.name, "solar");
+    }
+
+    #[test]
+    fn test_lerp_midpoint() {
+        let c = lerp_color(Color::Rgb(0, 100, 200), Color::Rgb(200, 100, 0), 0.5);
+        assert_eq!(c, Color::Rgb(100, 100, 100));
+    }
+
+    #[test]
+    fn test_lerp_reset_snaps() {
+        assert_eq!(
+            lerp_color(Color::Reset, Color::Rgb(0, 0, 0), 0.4),
+            Color::Reset
+        );
+        assert_eq!(
+            lerp_color(Color::Reset, Color::Rgb(0, 0, 0), 0.6),
+            Color::Rgb(0, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_lerp_named_colors_blend() {
+        assert_eq!(
+            lerp_color(Color::Red, Color::Rgb(0, 0, 0), 0.5),
+            Color::Rgb(103, 0, 0)
+        );
+        assert_eq!(
+            lerp_color(Color::Indexed(196), Color::White, 0.0),
+            Color::Rgb(255, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_indexed_rgb() {
+        assert_eq!(indexed_rgb(16), (0, 0, 0));
+        assert_eq!(indexed_rgb(231), (255, 255, 255));
+        assert_eq!(indexed_rgb(232), (8, 8, 8));
+        assert_eq!(indexed_rgb(255), (238, 238, 238));
+    }
+
+    #[test]
+    fn test_transition_progress() {
+        let mut tr = ThemeTransition::new(Theme::void(), 2.0);
+        assert_eq!(tr.progress(), 0.0);
+        tr.advance(1.0);
+        assert!((tr.progress() - 0.5).abs() < 1e-6);
+        assert!(!tr.is_finished());
+        tr.advance(1.5);
+        assert!(tr.is_finished());
+        assert_eq!(tr.apply(&Theme::solar()).accent, Theme::solar().accent);
+    }
+
+    #[test]
+    fn test_transition_zero_duration() {
+        let tr = ThemeTransition::new(Theme::void(), 0.0);
+        assert!(tr.is_finished());
+        assert_eq!(tr.progress(), 1.0);
+    }
+
+    #[test]
+    fn test_theme_from_toml() {
+        let t = Theme::from_toml(
+            "mine",
+            r##"
+                base = "nebula"
+                accent = "#102030"
+
+                [gradient]
+                mode = "vertical"
+                stops = ["#000000", "#ffffff"]
+            "##,
+        )
+        .unwrap();
+        assert_eq!(t.name, "mine");
+        assert_eq!(t.accent, Color::Rgb(16, 32, 48));
+        assert_eq!(t.bg, Theme::nebula().bg);
+        let g = t.gradient.unwrap();
+        assert_eq!(g.mode, GradientMode::Vertical);
+        assert_eq!(g.stops.len(), 2);
+    }
+
+    #[test]
+    fn test_theme_from_toml_key_spelling() {
+        let t = Theme::from_toml(
+            "mine",
+            r##"
+                Base = "luna"
+                star-bright = "#102030"
+                Shooting-Star = "#405060"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(t.star_bright, Color::Rgb(16, 32, 48));
+        assert_eq!(t.shooting_star, Color::Rgb(64, 80, 96));
+        assert_eq!(t.bg, Theme::luna().bg);
+    }
+
+    #[test]
+    fn test_theme_from_toml_errors() {
+        assert!(Theme::from_toml("x", "accent = \"nope\"").is_err());
+        assert!(Theme::from_toml("x", "sparkle = \"#ffffff\"").is_err());
+        assert!(Theme::from_toml("x", "base = \"mars\"").is_err());
+        assert!(Theme::from_toml("x", "[gradient]\nstops = [\"#ffffff\"]").is_err());
+    }
+
+    #[test]
+    fn test_load_unknown_theme() {
+        let err = Theme::load("/nonexistent/theme.toml").unwrap_err();
+        assert!(err.contains("unknown theme"));
+    }
+
+    #[test]
+    fn test_gradient_horizontal() {
+        let g = Gradient {
+            mode: GradientMode::Horizontal,
+            stops: vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 200, 200)],
+        };
+        assert_eq!(g.color_at(0.0, 0.7, 0.0), Color::Rgb(0, 0, 0));
+        assert_eq!(g.color_at(0.5, 0.7, 0.0), Color::Rgb(100, 100, 100));
+        assert_eq!(g.color_at(1.0, 0.7, 0.0), Color::Rgb(200, 200, 200));
+    }
+
+    #[test]
+    fn test_gradient_rainbow_wraps() {
+        let g = Gradient {
+            mode: GradientMode::Rainbow,
+            stops: vec![Color::Rgb(0, 0, 0), Color::Rgb(200, 200, 200)],
+        };
+        // A full cycle later the sweep is back where it started
+        let period = 1.0 / RAINBOW_SPEED;
+        assert_eq!(g.color_at(0.3, 0.0, 0.0), g.color_at(0.3, 0.0, period));
+        assert_ne!(
+            g.color_at(0.3, 0.0, 0.0),
+            g.color_at(0.3, 0.0, period / 3.0)
+        );
+    }
+
+    #[test]
+    fn test_with_gradient_mode_defaults_to_accent() {
+        let t = Theme::void().with_gradient_mode(GradientMode::Diagonal);
+        let g = t.gradient.unwrap();
+        assert_eq!(g.stops, vec![Theme::void().accent, Theme::void().secondary]);
+    }
+
+    #[test]
+    fn test_parse_color_setting() {
+        assert_eq!(
+            parse_color_setting("bg=#101020"),
+            Ok(("bg".to_string(), Color::Rgb(16, 16, 32)))
+        );
+        assert_eq!(
+            parse_color_setting("star-dim = gray"),
+            Ok(("star_dim".to_string(), Color::Rgb(128, 128, 128)))
+        );
+        assert_eq!(
+            parse_color_setting("BG=red"),
+            Ok(("bg".to_string(), Color::Red))
+        );
+        assert!(parse_color_setting("bg").is_err());
+        assert!(parse_color_setting("sparkle=red").is_err());
+        assert!(parse_color_setting("bg=nope").is_err());
+    }
+
+    #[test]
+    fn test_overrides_apply_in_order() {
+        let overrides = ThemeOverrides {
+            accent: Some(Color::Rgb(1, 1, 1)),
+            colors: vec![
+                ("secondary".into(), Color::Rgb(2, 2, 2)),
+                ("accent".into(), Color::Rgb(3, 3, 3)),
+            ],
+            gradient: None,
+        };
+        let t = overrides.apply(Theme::luna());
+        assert_eq!(t.accent, Color::Rgb(3, 3, 3));
+        assert_eq!(t.secondary, Color::Rgb(2, 2, 2));
+        assert_eq!(t.bg, Theme::luna().bg);
+    }
+
+    #[test]
+    fn test_to_toml_round_trip() {
+        let mut original = Theme::nebula().with_gradient_mode(GradientMode::Rainbow);
+        original.secondary = Color::Indexed(99);
+        original.date_color = Color::Cyan;
+        let parsed = Theme::from_toml("ignored", &original.to_toml()).unwrap();
+        assert_eq!(parsed.name, "nebula");
+        for key in COLOR_KEYS {
+            assert_eq!(parsed.color(key), original.color(key), "key {key}");
+        }
+        assert_eq!(parsed.gradient, original.gradient);
+    }
+
+    #[test]
+    fn test_to_toml_escapes_name() {
+        let theme = Theme {
+            name: r#"my "neon" \ theme"#.into(),
+            ..Theme::void()
+        };
+        let parsed = Theme::from_toml("ignored", &theme.to_toml()).unwrap();
+        assert_eq!(parsed.name, theme.name);
+    }
+
     #[test]
     fn test_accent_override() {
-        let t = Theme::void().with_accent_override("#ff0000");
+        let t = Theme::void().with_accent_override(Color::Rgb(255, 0, 0));
         assert_eq!(t.accent, Color::Rgb(255, 0, 0));
     }
 }
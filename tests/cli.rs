@@ -38,3 +38,139 @@ fn invalid_fps_range_exits_nonzero() {
         "out-of-range --fps should exit non-zero"
     );
 }
+
+#[test]
+fn unknown_theme_exits_nonzero() {
+    let output = stardial()
+        .args(["--theme", "no-such-theme"])
+        .output()
+        .unwrap();
+    assert!(
+        !output.status.success(),
+        "unknown theme should exit non-zero"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unknown theme"));
+}
+
+#[test]
+fn invalid_color_suggests_name() {
+    let output = stardial().args(["--color", "hotpnk"]).output().unwrap();
+    assert!(
+        !output.status.success(),
+        "invalid color should exit non-zero"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("did you mean `hotpink`"),
+        "error should suggest the closest name, got: {stderr}"
+    );
+}
+
+#[test]
+fn theme_generate_prints_theme_file() {
+    let output = stardial()
+        .args(["theme", "generate", "#ff6ac1", "--name", "sakura"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "theme generate should exit 0");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("name = \"sakura\""));
+    assert!(stdout.contains("accent = \"#ff6ac1\""));
+    assert!(stdout.contains("bg = "));
+}
+
+#[test]
+fn themes_lists_builtin_and_user_themes() {
+    let config = std::env::temp_dir().join(format!("stardial-themes-{}", std::process::id()));
+    let dir = config.join("stardial").join("themes");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("sakura.toml"),
+        "base = \"nebula\"\naccent = \"#ffb7c5\"\n",
+    )
+    .unwrap();
+
+    let output = stardial()
+        .arg("themes")
+        .env("XDG_CONFIG_HOME", &config)
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&config);
+    assert!(output.status.success(), "themes should exit 0");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for name in ["void", "nebula", "luna", "solar", "sakura"] {
+        assert!(
+            stdout.contains(name),
+            "missing theme `{name}` in:\n{stdout}"
+        );
+    }
+}
+
+#[test]
+fn themes_preview_renders_samples() {
+    let output = stardial()
+        .args(["--mono", "themes", "--preview"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "themes --preview should exit 0");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains('█'), "preview should draw clock digits");
+    assert!(stdout.contains("solar"), "preview should label each theme");
+    assert!(
+        !stdout.contains('\x1b'),
+        "mono or piped preview should have no escapes"
+    );
+}
+
+#[test]
+fn invalid_config_value_exits_nonzero() {
+    let dir = std::env::temp_dir().join(format!("stardial-config-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "fps = 999\n").unwrap();
+
+    let output = stardial()
+        .arg("--config")
+        .arg(&path)
+        .args(["themes"])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        !output.status.success(),
+        "out-of-range config value should exit non-zero"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("config.toml"), "error should name the file");
+    assert!(stderr.contains("--fps"), "error should name the option");
+}
+
+#[test]
+fn command_line_overrides_config() {
+    let dir = std::env::temp_dir().join(format!("stardial-override-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+    std::fs::write(&path, "fps = 999\n").unwrap();
+
+    let output = stardial()
+        .arg("--config")
+        .arg(&path)
+        .args(["--fps", "10", "themes"])
+        .output()
+        .unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+    assert!(
+        output.status.success(),
+        "--fps on the command line should replace the config value"
+    );
+}
+
+#[test]
+fn missing_config_file_exits_nonzero() {
+    let output = stardial()
+        .args(["--config", "/no/such/stardial.toml", "themes"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}